@@ -0,0 +1,2 @@
+ALTER TABLE task
+ADD COLUMN scheduled_date date;
\ No newline at end of file
@@ -0,0 +1,6 @@
+CREATE TABLE task_dependency (
+    blocker_id integer NOT NULL REFERENCES task (id) ON DELETE CASCADE,
+    blocked_id integer NOT NULL REFERENCES task (id) ON DELETE CASCADE,
+    PRIMARY KEY (blocker_id, blocked_id),
+    CHECK (blocker_id <> blocked_id)
+);
\ No newline at end of file
@@ -0,0 +1,2 @@
+ALTER TABLE task
+ADD COLUMN estimate_minutes integer;
\ No newline at end of file
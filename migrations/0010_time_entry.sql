@@ -0,0 +1,6 @@
+CREATE TABLE time_entry (
+    id SERIAL PRIMARY KEY,
+    task_id integer NOT NULL REFERENCES task (id) ON DELETE CASCADE,
+    started_at timestamptz NOT NULL DEFAULT now(),
+    ended_at timestamptz
+);
\ No newline at end of file
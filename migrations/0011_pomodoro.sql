@@ -0,0 +1,6 @@
+CREATE TABLE pomodoro (
+    id SERIAL PRIMARY KEY,
+    task_id integer NOT NULL REFERENCES task (id) ON DELETE CASCADE,
+    started_at timestamptz NOT NULL,
+    ended_at timestamptz NOT NULL
+);
\ No newline at end of file
@@ -0,0 +1,22 @@
+CREATE FUNCTION notify_change() RETURNS trigger AS $$ BEGIN PERFORM pg_notify(
+        'tuitask_change',
+        TG_TABLE_NAME || ':' || TG_OP || ':' || CASE
+            WHEN TG_OP = 'DELETE' THEN OLD.id
+            ELSE NEW.id
+        END
+    );
+RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+CREATE TRIGGER task_notify_change
+AFTER
+INSERT
+    OR
+UPDATE
+    OR DELETE ON task FOR EACH ROW EXECUTE FUNCTION notify_change();
+CREATE TRIGGER category_notify_change
+AFTER
+INSERT
+    OR
+UPDATE
+    OR DELETE ON category FOR EACH ROW EXECUTE FUNCTION notify_change();
\ No newline at end of file
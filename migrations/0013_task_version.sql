@@ -0,0 +1,2 @@
+ALTER TABLE task
+ADD COLUMN version integer NOT NULL DEFAULT 1;
\ No newline at end of file
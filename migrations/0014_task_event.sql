@@ -0,0 +1,11 @@
+CREATE TABLE task_event (
+    id SERIAL PRIMARY KEY,
+    task_id integer NOT NULL,
+    occurred_at timestamptz NOT NULL DEFAULT now(),
+    kind text NOT NULL CHECK (kind IN ('create', 'update', 'delete')),
+    field text NOT NULL,
+    old_value text,
+    new_value text
+);
+
+CREATE INDEX task_event_task_id ON task_event (task_id);
\ No newline at end of file
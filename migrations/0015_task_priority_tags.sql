@@ -0,0 +1,3 @@
+ALTER TABLE task
+ADD COLUMN priority smallint NOT NULL DEFAULT 0,
+ADD COLUMN tags text[] NOT NULL DEFAULT '{}';
\ No newline at end of file
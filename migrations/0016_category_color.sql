@@ -0,0 +1,2 @@
+ALTER TABLE category
+ADD COLUMN color varchar;
\ No newline at end of file
@@ -0,0 +1,683 @@
+use crate::key::Key;
+
+// Everything that can be done with a key, bound below or in keymap.toml and listed in the palette
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    ShowUpcoming,
+    ShowCategories,
+    NextView,
+    Stats,
+    Messages,
+    Undo,
+    Redo,
+    CommandLine,
+    CommandPalette,
+    ToggleMouse,
+    Quit,
+    ScrollDown,
+    ScrollUp,
+    ResetCursor,
+    EditTask,
+    AddTask,
+    QuickAdd,
+    CompleteTask,
+    DeleteTask,
+    ToggleTimer,
+    Focus,
+    EditBlockers,
+    History,
+    ToggleDetails,
+    RescheduleOverdue,
+    EditCategory,
+    AddCategory,
+    ToggleSelect,
+    SelectRange,
+    SelectGroup,
+    CompleteSelected,
+    DeleteSelected,
+    MoveSelected,
+    ShiftSelected,
+    RecategorizeSelected,
+    ClearSelection,
+    YankText,
+    YankMarkdown,
+    NextField,
+    PreviousField,
+    NextCategory,
+    PreviousCategory,
+    Save,
+    Confirm,
+    Cancel,
+    Help,
+    TogglePause,
+    SkipPhase,
+    PickMine,
+    PickTheirs,
+    KeepMine,
+    KeepTheirs,
+    ResolveConflicts,
+    CompleteCommand,
+    PreviousCommand,
+    NextCommand,
+}
+
+impl Action {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::ShowUpcoming => "Show upcoming",
+            Action::ShowCategories => "Show categories",
+            Action::NextView => "Switch view",
+            Action::Stats => "Show stats",
+            Action::Messages => "Show messages",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+            Action::CommandLine => "Open command line",
+            Action::CommandPalette => "Open command palette",
+            Action::ToggleMouse => "Toggle mouse capture",
+            Action::Quit => "Quit",
+            Action::ScrollDown => "Scroll down",
+            Action::ScrollUp => "Scroll up",
+            Action::ResetCursor => "Jump to today",
+            Action::EditTask => "Edit task",
+            Action::AddTask => "Add task",
+            Action::QuickAdd => "Quick add task",
+            Action::CompleteTask => "Complete task",
+            Action::DeleteTask => "Delete task",
+            Action::ToggleTimer => "Start/stop timer",
+            Action::Focus => "Focus on task",
+            Action::EditBlockers => "Edit blocked by",
+            Action::History => "Show task history",
+            Action::ToggleDetails => "Show/hide task details",
+            Action::RescheduleOverdue => "Reschedule overdue tasks",
+            Action::EditCategory => "Rename category",
+            Action::AddCategory => "Add category",
+            Action::ToggleSelect => "Toggle selection",
+            Action::SelectRange => "Select range",
+            Action::SelectGroup => "Select day",
+            Action::CompleteSelected => "Complete selected",
+            Action::DeleteSelected => "Delete selected",
+            Action::MoveSelected => "Move selected",
+            Action::ShiftSelected => "Shift selected",
+            Action::RecategorizeSelected => "Change category of selected",
+            Action::ClearSelection => "Clear selection",
+            Action::YankText => "Copy tasks as text",
+            Action::YankMarkdown => "Copy tasks as Markdown",
+            Action::NextField => "Next field",
+            Action::PreviousField => "Previous field",
+            Action::NextCategory => "Next category",
+            Action::PreviousCategory => "Previous category",
+            Action::Save => "Save",
+            Action::Confirm => "Confirm",
+            Action::Cancel => "Cancel",
+            Action::Help => "Show key bindings",
+            Action::TogglePause => "Pause/resume",
+            Action::SkipPhase => "Skip to next interval",
+            Action::PickMine => "Keep my version of field",
+            Action::PickTheirs => "Take their version of field",
+            Action::KeepMine => "Keep my version of task",
+            Action::KeepTheirs => "Take their version of task",
+            Action::ResolveConflicts => "Resolve edit conflicts",
+            Action::CompleteCommand => "Complete command",
+            Action::PreviousCommand => "Previous command",
+            Action::NextCommand => "Next command",
+        }
+    }
+
+    // Short label used in the footer hints
+    pub fn hint(&self) -> &'static str {
+        match self {
+            Action::ShowUpcoming => "Upcoming",
+            Action::ShowCategories => "Categories",
+            Action::NextView => "Next view",
+            Action::Stats => "Stats",
+            Action::Messages => "Messages",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+            Action::CommandLine => "Command",
+            Action::CommandPalette => "Palette",
+            Action::ToggleMouse => "Mouse",
+            Action::Quit => "Quit",
+            Action::ScrollDown => "Down",
+            Action::ScrollUp => "Up",
+            Action::ResetCursor => "Today",
+            Action::EditTask => "Edit",
+            Action::AddTask => "Add",
+            Action::QuickAdd => "Quick add",
+            Action::CompleteTask => "Complete",
+            Action::DeleteTask => "Delete",
+            Action::ToggleTimer => "Timer",
+            Action::Focus => "Focus",
+            Action::EditBlockers => "Blocked by",
+            Action::History => "History",
+            Action::ToggleDetails => "Details",
+            Action::RescheduleOverdue => "Overdue",
+            Action::EditCategory => "Edit",
+            Action::AddCategory => "Add",
+            Action::ToggleSelect => "Select",
+            Action::SelectRange => "Range",
+            Action::SelectGroup => "Day",
+            Action::CompleteSelected => "Complete",
+            Action::DeleteSelected => "Delete",
+            Action::MoveSelected => "Move",
+            Action::ShiftSelected => "Shift",
+            Action::RecategorizeSelected => "Category",
+            Action::ClearSelection => "Clear",
+            Action::YankText => "Copy",
+            Action::YankMarkdown => "Copy Markdown",
+            Action::NextField => "Next field",
+            Action::PreviousField => "Previous field",
+            Action::NextCategory => "Next category",
+            Action::PreviousCategory => "Previous category",
+            Action::Save => "Save",
+            Action::Confirm => "Confirm",
+            Action::Cancel => "Exit",
+            Action::Help => "Help",
+            Action::TogglePause => "Pause",
+            Action::SkipPhase => "Next",
+            Action::PickMine => "Mine",
+            Action::PickTheirs => "Theirs",
+            Action::KeepMine => "Keep mine",
+            Action::KeepTheirs => "Keep theirs",
+            Action::ResolveConflicts => "Conflicts",
+            Action::CompleteCommand => "Complete",
+            Action::PreviousCommand => "Previous",
+            Action::NextCommand => "Next",
+        }
+    }
+
+    // Name used for the action in keymap.toml
+    pub fn id(&self) -> &'static str {
+        match self {
+            Action::ShowUpcoming => "show_upcoming",
+            Action::ShowCategories => "show_categories",
+            Action::NextView => "next_view",
+            Action::Stats => "stats",
+            Action::Messages => "messages",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::CommandLine => "command_line",
+            Action::CommandPalette => "command_palette",
+            Action::ToggleMouse => "toggle_mouse",
+            Action::Quit => "quit",
+            Action::ScrollDown => "scroll_down",
+            Action::ScrollUp => "scroll_up",
+            Action::ResetCursor => "reset_cursor",
+            Action::EditTask => "edit_task",
+            Action::AddTask => "add_task",
+            Action::QuickAdd => "quick_add",
+            Action::CompleteTask => "complete_task",
+            Action::DeleteTask => "delete_task",
+            Action::ToggleTimer => "toggle_timer",
+            Action::Focus => "focus",
+            Action::EditBlockers => "edit_blockers",
+            Action::History => "history",
+            Action::ToggleDetails => "toggle_details",
+            Action::RescheduleOverdue => "reschedule_overdue",
+            Action::EditCategory => "edit_category",
+            Action::AddCategory => "add_category",
+            Action::ToggleSelect => "toggle_select",
+            Action::SelectRange => "select_range",
+            Action::SelectGroup => "select_group",
+            Action::CompleteSelected => "complete_selected",
+            Action::DeleteSelected => "delete_selected",
+            Action::MoveSelected => "move_selected",
+            Action::ShiftSelected => "shift_selected",
+            Action::RecategorizeSelected => "recategorize_selected",
+            Action::ClearSelection => "clear_selection",
+            Action::YankText => "yank_text",
+            Action::YankMarkdown => "yank_markdown",
+            Action::NextField => "next_field",
+            Action::PreviousField => "previous_field",
+            Action::NextCategory => "next_category",
+            Action::PreviousCategory => "previous_category",
+            Action::Save => "save",
+            Action::Confirm => "confirm",
+            Action::Cancel => "cancel",
+            Action::Help => "help",
+            Action::TogglePause => "toggle_pause",
+            Action::SkipPhase => "skip_phase",
+            Action::PickMine => "pick_mine",
+            Action::PickTheirs => "pick_theirs",
+            Action::KeepMine => "keep_mine",
+            Action::KeepTheirs => "keep_theirs",
+            Action::ResolveConflicts => "resolve_conflicts",
+            Action::CompleteCommand => "complete_command",
+            Action::PreviousCommand => "previous_command",
+            Action::NextCommand => "next_command",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Action> {
+        Action::ALL.iter().find(|a| a.id() == id).copied()
+    }
+
+    pub const ALL: [Action; 56] = [
+        Action::ShowUpcoming,
+        Action::ShowCategories,
+        Action::NextView,
+        Action::Stats,
+        Action::Messages,
+        Action::Undo,
+        Action::Redo,
+        Action::CommandLine,
+        Action::CommandPalette,
+        Action::ToggleMouse,
+        Action::Quit,
+        Action::ScrollDown,
+        Action::ScrollUp,
+        Action::ResetCursor,
+        Action::EditTask,
+        Action::AddTask,
+        Action::QuickAdd,
+        Action::CompleteTask,
+        Action::DeleteTask,
+        Action::ToggleTimer,
+        Action::Focus,
+        Action::EditBlockers,
+        Action::History,
+        Action::ToggleDetails,
+        Action::RescheduleOverdue,
+        Action::EditCategory,
+        Action::AddCategory,
+        Action::ToggleSelect,
+        Action::SelectRange,
+        Action::SelectGroup,
+        Action::CompleteSelected,
+        Action::DeleteSelected,
+        Action::MoveSelected,
+        Action::ShiftSelected,
+        Action::RecategorizeSelected,
+        Action::ClearSelection,
+        Action::YankText,
+        Action::YankMarkdown,
+        Action::NextField,
+        Action::PreviousField,
+        Action::NextCategory,
+        Action::PreviousCategory,
+        Action::Save,
+        Action::Confirm,
+        Action::Cancel,
+        Action::Help,
+        Action::TogglePause,
+        Action::SkipPhase,
+        Action::PickMine,
+        Action::PickTheirs,
+        Action::KeepMine,
+        Action::KeepTheirs,
+        Action::ResolveConflicts,
+        Action::CompleteCommand,
+        Action::PreviousCommand,
+        Action::NextCommand,
+    ];
+
+    // Heading the action is listed under in the help overlay, one of GROUPS
+    pub fn group(&self) -> &'static str {
+        match self {
+            Action::ScrollDown
+            | Action::ScrollUp
+            | Action::ResetCursor
+            | Action::NextField
+            | Action::PreviousField
+            | Action::NextCategory
+            | Action::PreviousCategory
+            | Action::PreviousCommand
+            | Action::NextCommand => "Moving",
+            Action::EditTask
+            | Action::AddTask
+            | Action::QuickAdd
+            | Action::CompleteTask
+            | Action::DeleteTask
+            | Action::ToggleTimer
+            | Action::Focus
+            | Action::EditBlockers
+            | Action::History
+            | Action::RescheduleOverdue => "Tasks",
+            Action::EditCategory | Action::AddCategory => "Categories",
+            Action::ToggleSelect
+            | Action::SelectRange
+            | Action::SelectGroup
+            | Action::CompleteSelected
+            | Action::DeleteSelected
+            | Action::MoveSelected
+            | Action::ShiftSelected
+            | Action::RecategorizeSelected
+            | Action::ClearSelection
+            | Action::YankText
+            | Action::YankMarkdown => "Selection",
+            Action::Save
+            | Action::Confirm
+            | Action::Cancel
+            | Action::CompleteCommand
+            | Action::Undo
+            | Action::Redo => "Editing",
+            Action::TogglePause | Action::SkipPhase => "Focus",
+            Action::PickMine
+            | Action::PickTheirs
+            | Action::KeepMine
+            | Action::KeepTheirs
+            | Action::ResolveConflicts => "Conflicts",
+            Action::ShowUpcoming
+            | Action::ShowCategories
+            | Action::NextView
+            | Action::Stats
+            | Action::Messages
+            | Action::ToggleDetails
+            | Action::CommandLine
+            | Action::CommandPalette => "Views",
+            Action::Help | Action::ToggleMouse | Action::Quit => "General",
+        }
+    }
+
+    // Order of the headings in the help overlay
+    pub const GROUPS: [&'static str; 9] = [
+        "Moving",
+        "Tasks",
+        "Categories",
+        "Selection",
+        "Editing",
+        "Focus",
+        "Conflicts",
+        "Views",
+        "General",
+    ];
+}
+
+// Where a binding applies, Selection only while tasks are selected and a pop up's own context
+// only while it's open
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Context {
+    Global,
+    Upcoming,
+    Categories,
+    Selection,
+    TaskEditor,
+    PopUp, // every pop up, after its own context
+    CategoryEditor,
+    Rescheduler,
+    DependencyEditor,
+    Stats,
+    Focus,
+    ConflictResolver,
+    History,
+    Messages,
+    Shifter,
+    CategoryPicker,
+    QuickAdd,
+    CommandLine,
+    CommandPalette,
+    Help,
+}
+
+impl Context {
+    // Name of the context's section in keymap.toml
+    pub fn id(&self) -> &'static str {
+        match self {
+            Context::Global => "global",
+            Context::Upcoming => "upcoming",
+            Context::Categories => "categories",
+            Context::Selection => "selection",
+            Context::TaskEditor => "task_editor",
+            Context::PopUp => "pop_up",
+            Context::CategoryEditor => "category_editor",
+            Context::Rescheduler => "rescheduler",
+            Context::DependencyEditor => "dependency_editor",
+            Context::Stats => "stats",
+            Context::Focus => "focus",
+            Context::ConflictResolver => "conflict_resolver",
+            Context::History => "history",
+            Context::Messages => "messages",
+            Context::Shifter => "shifter",
+            Context::CategoryPicker => "category_picker",
+            Context::QuickAdd => "quick_add",
+            Context::CommandLine => "command_line",
+            Context::CommandPalette => "command_palette",
+            Context::Help => "help",
+        }
+    }
+
+    // Context looked up right after this one whose bindings it's checked against, the task editor
+    // and help have all their keys in their own
+    pub fn fallback(&self) -> Option<Context> {
+        match self {
+            Context::Upcoming | Context::Categories => Some(Context::Global),
+            Context::Global
+            | Context::Selection
+            | Context::TaskEditor
+            | Context::PopUp
+            | Context::Help => None,
+            _ => Some(Context::PopUp),
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Context> {
+        Context::ALL.into_iter().find(|c| c.id() == id)
+    }
+
+    pub const ALL: [Context; 20] = [
+        Context::Global,
+        Context::Upcoming,
+        Context::Categories,
+        Context::Selection,
+        Context::TaskEditor,
+        Context::PopUp,
+        Context::CategoryEditor,
+        Context::Rescheduler,
+        Context::DependencyEditor,
+        Context::Stats,
+        Context::Focus,
+        Context::ConflictResolver,
+        Context::History,
+        Context::Messages,
+        Context::Shifter,
+        Context::CategoryPicker,
+        Context::QuickAdd,
+        Context::CommandLine,
+        Context::CommandPalette,
+        Context::Help,
+    ];
+}
+
+pub const DEFAULT_BINDINGS: &[(Context, &[Key], Action)] = &[
+    (Context::Selection, &[Key::Enter], Action::CompleteSelected),
+    (Context::Selection, &[Key::Char('m')], Action::MoveSelected),
+    (Context::Selection, &[Key::Char('>')], Action::ShiftSelected),
+    (
+        Context::Selection,
+        &[Key::Char('c')],
+        Action::RecategorizeSelected,
+    ),
+    (
+        Context::Selection,
+        &[Key::Char('d'), Key::Char('d')],
+        Action::DeleteSelected,
+    ),
+    (Context::Selection, &[Key::Esc], Action::ClearSelection),
+    (Context::Global, &[Key::Number('1')], Action::ShowUpcoming),
+    (Context::Global, &[Key::Number('2')], Action::ShowCategories),
+    (
+        Context::Global,
+        &[Key::Char('g'), Key::Char('t')],
+        Action::NextView,
+    ),
+    (Context::Global, &[Key::Char(' ')], Action::ToggleSelect),
+    (Context::Global, &[Key::Char('v')], Action::SelectRange),
+    (Context::Global, &[Key::Char('V')], Action::SelectGroup),
+    (
+        Context::Global,
+        &[Key::Char('y'), Key::Char('y')],
+        Action::YankText,
+    ),
+    (
+        Context::Global,
+        &[Key::Char('y'), Key::Char('m')],
+        Action::YankMarkdown,
+    ),
+    (Context::Global, &[Key::Char('u')], Action::Undo),
+    (Context::Global, &[Key::Ctrl('r')], Action::Redo),
+    (Context::Global, &[Key::Char('s')], Action::Stats),
+    (Context::Global, &[Key::Char('L')], Action::Messages),
+    (Context::Global, &[Key::Char(':')], Action::CommandLine),
+    (Context::Global, &[Key::Ctrl('p')], Action::CommandPalette),
+    (Context::Global, &[Key::Char('M')], Action::ToggleMouse),
+    (Context::Global, &[Key::Char('C')], Action::ResolveConflicts),
+    (Context::Global, &[Key::Char('?')], Action::Help),
+    (Context::Global, &[Key::Char('q')], Action::Quit),
+    (Context::Global, &[Key::Esc], Action::Quit),
+    (Context::Global, &[Key::Ctrl('c')], Action::Quit),
+    (Context::Upcoming, &[Key::Char('j')], Action::ScrollDown),
+    (Context::Upcoming, &[Key::Down], Action::ScrollDown),
+    (Context::Upcoming, &[Key::Char('k')], Action::ScrollUp),
+    (Context::Upcoming, &[Key::Up], Action::ScrollUp),
+    (Context::Upcoming, &[Key::Char('r')], Action::ResetCursor),
+    (
+        Context::Upcoming,
+        &[Key::Char('g'), Key::Char('g')],
+        Action::ResetCursor,
+    ),
+    (Context::Upcoming, &[Key::Home], Action::ResetCursor),
+    (Context::Upcoming, &[Key::Char('e')], Action::EditTask),
+    (Context::Upcoming, &[Key::Char('a')], Action::AddTask),
+    (Context::Upcoming, &[Key::Char('n')], Action::QuickAdd),
+    (Context::Upcoming, &[Key::Enter], Action::CompleteTask),
+    (
+        Context::Upcoming,
+        &[Key::Char('d'), Key::Char('d')],
+        Action::DeleteTask,
+    ),
+    (Context::Upcoming, &[Key::Char('t')], Action::ToggleTimer),
+    (Context::Upcoming, &[Key::Char('f')], Action::Focus),
+    (Context::Upcoming, &[Key::Char('b')], Action::EditBlockers),
+    (Context::Upcoming, &[Key::Char('h')], Action::History),
+    (Context::Upcoming, &[Key::Char('i')], Action::ToggleDetails),
+    (
+        Context::Upcoming,
+        &[Key::Char('o')],
+        Action::RescheduleOverdue,
+    ),
+    (Context::Categories, &[Key::Char('j')], Action::ScrollDown),
+    (Context::Categories, &[Key::Down], Action::ScrollDown),
+    (Context::Categories, &[Key::Char('k')], Action::ScrollUp),
+    (Context::Categories, &[Key::Up], Action::ScrollUp),
+    (Context::Categories, &[Key::Char('e')], Action::EditCategory),
+    (Context::Categories, &[Key::Char('a')], Action::AddCategory),
+    (Context::TaskEditor, &[Key::Tab], Action::NextField),
+    (Context::TaskEditor, &[Key::ShiftTab], Action::PreviousField),
+    (Context::TaskEditor, &[Key::Char('j')], Action::NextCategory),
+    (Context::TaskEditor, &[Key::Down], Action::NextCategory),
+    (
+        Context::TaskEditor,
+        &[Key::Char('k')],
+        Action::PreviousCategory,
+    ),
+    (Context::TaskEditor, &[Key::Up], Action::PreviousCategory),
+    (Context::TaskEditor, &[Key::Enter], Action::Save),
+    (Context::TaskEditor, &[Key::Esc], Action::Cancel),
+    (Context::TaskEditor, &[Key::Ctrl('c')], Action::Cancel),
+    (Context::TaskEditor, &[Key::F(1)], Action::Help),
+    (Context::PopUp, &[Key::Esc], Action::Cancel),
+    (Context::PopUp, &[Key::Ctrl('c')], Action::Cancel),
+    (Context::PopUp, &[Key::F(1)], Action::Help),
+    (Context::CategoryEditor, &[Key::Enter], Action::Confirm),
+    (Context::Rescheduler, &[Key::Enter], Action::Confirm),
+    (Context::Rescheduler, &[Key::Tab], Action::NextField),
+    (
+        Context::Rescheduler,
+        &[Key::ShiftTab],
+        Action::PreviousField,
+    ),
+    (Context::DependencyEditor, &[Key::Enter], Action::Confirm),
+    (Context::DependencyEditor, &[Key::Down], Action::ScrollDown),
+    (Context::DependencyEditor, &[Key::Up], Action::ScrollUp),
+    (Context::Stats, &[Key::Char('s')], Action::Cancel),
+    (Context::Stats, &[Key::Char('j')], Action::ScrollDown),
+    (Context::Stats, &[Key::Down], Action::ScrollDown),
+    (Context::Stats, &[Key::Char('k')], Action::ScrollUp),
+    (Context::Stats, &[Key::Up], Action::ScrollUp),
+    (Context::Stats, &[Key::Char('?')], Action::Help),
+    (Context::Focus, &[Key::Char(' ')], Action::TogglePause),
+    (Context::Focus, &[Key::Char('n')], Action::SkipPhase),
+    (Context::Focus, &[Key::Char('?')], Action::Help),
+    (
+        Context::ConflictResolver,
+        &[Key::Char('j')],
+        Action::ScrollDown,
+    ),
+    (Context::ConflictResolver, &[Key::Down], Action::ScrollDown),
+    (
+        Context::ConflictResolver,
+        &[Key::Char('k')],
+        Action::ScrollUp,
+    ),
+    (Context::ConflictResolver, &[Key::Up], Action::ScrollUp),
+    (
+        Context::ConflictResolver,
+        &[Key::Char('h')],
+        Action::PickMine,
+    ),
+    (Context::ConflictResolver, &[Key::Left], Action::PickMine),
+    (
+        Context::ConflictResolver,
+        &[Key::Char('l')],
+        Action::PickTheirs,
+    ),
+    (Context::ConflictResolver, &[Key::Right], Action::PickTheirs),
+    (
+        Context::ConflictResolver,
+        &[Key::Char('m')],
+        Action::KeepMine,
+    ),
+    (
+        Context::ConflictResolver,
+        &[Key::Char('t')],
+        Action::KeepTheirs,
+    ),
+    (Context::ConflictResolver, &[Key::Enter], Action::Confirm),
+    (Context::ConflictResolver, &[Key::Char('?')], Action::Help),
+    (Context::History, &[Key::Char('h')], Action::Cancel),
+    (Context::History, &[Key::Char('j')], Action::ScrollDown),
+    (Context::History, &[Key::Down], Action::ScrollDown),
+    (Context::History, &[Key::Char('k')], Action::ScrollUp),
+    (Context::History, &[Key::Up], Action::ScrollUp),
+    (Context::History, &[Key::Char('?')], Action::Help),
+    (Context::Messages, &[Key::Char('L')], Action::Cancel),
+    (Context::Messages, &[Key::Char('j')], Action::ScrollDown),
+    (Context::Messages, &[Key::Down], Action::ScrollDown),
+    (Context::Messages, &[Key::Char('k')], Action::ScrollUp),
+    (Context::Messages, &[Key::Up], Action::ScrollUp),
+    (Context::Messages, &[Key::Char('?')], Action::Help),
+    (Context::Shifter, &[Key::Enter], Action::Confirm),
+    (
+        Context::CategoryPicker,
+        &[Key::Char('j')],
+        Action::ScrollDown,
+    ),
+    (Context::CategoryPicker, &[Key::Down], Action::ScrollDown),
+    (Context::CategoryPicker, &[Key::Char('k')], Action::ScrollUp),
+    (Context::CategoryPicker, &[Key::Up], Action::ScrollUp),
+    (Context::CategoryPicker, &[Key::Enter], Action::Confirm),
+    (Context::CategoryPicker, &[Key::Char('?')], Action::Help),
+    (Context::QuickAdd, &[Key::Enter], Action::Confirm),
+    (Context::CommandLine, &[Key::Enter], Action::Confirm),
+    (Context::CommandLine, &[Key::Tab], Action::CompleteCommand),
+    (Context::CommandLine, &[Key::Up], Action::PreviousCommand),
+    (Context::CommandLine, &[Key::Down], Action::NextCommand),
+    (Context::CommandPalette, &[Key::Enter], Action::Confirm),
+    (Context::CommandPalette, &[Key::Ctrl('p')], Action::Cancel),
+    (Context::CommandPalette, &[Key::Up], Action::ScrollUp),
+    (Context::CommandPalette, &[Key::Ctrl('k')], Action::ScrollUp),
+    (Context::CommandPalette, &[Key::Down], Action::ScrollDown),
+    (
+        Context::CommandPalette,
+        &[Key::Ctrl('j')],
+        Action::ScrollDown,
+    ),
+    (Context::Help, &[Key::Char('j')], Action::ScrollDown),
+    (Context::Help, &[Key::Down], Action::ScrollDown),
+    (Context::Help, &[Key::Char('k')], Action::ScrollUp),
+    (Context::Help, &[Key::Up], Action::ScrollUp),
+    (Context::Help, &[Key::Char('?')], Action::Cancel),
+    (Context::Help, &[Key::Char('q')], Action::Cancel),
+    (Context::Help, &[Key::Esc], Action::Cancel),
+    (Context::Help, &[Key::Ctrl('c')], Action::Cancel),
+    (Context::Help, &[Key::F(1)], Action::Cancel),
+];
@@ -1,15 +1,47 @@
 mod categories;
 mod category_editor;
+mod category_picker;
+mod command_line;
+mod command_palette;
+mod conflict_resolver;
+mod dependency_editor;
+mod focus;
+mod help;
+mod history;
+mod messages;
+mod mouse;
+mod quick_add_bar;
+mod rescheduler;
+mod selection;
+mod shifter;
+mod stats;
 mod task_editor;
 mod upcoming;
 
+pub use mouse::ClickTarget;
+
 use crate::{
+    action::{Action, Context},
     category::Category,
+    config::Config,
     database::IOEvent,
-    key::Key,
-    task::{TaskDate, TaskList},
+    fuzzy,
+    key::{Code, Key},
+    keymap::{Binding, KeyMatch, Keymap},
+    mouse::Mouse,
+    quick_add,
+    status::StatusLog,
+    task::{Conflict, Task, TaskDate, TaskEvent, TaskList, TaskTimestamps},
+    theme::Theme,
+    tracking::{Pomodoro, Stats, Timer},
+    undo::{UndoEntry, UndoHistory},
 };
-use ratatui::widgets::ListState;
+use chrono::{Local, NaiveDate};
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use ratatui::{prelude::Rect, widgets::ListState};
+use std::{collections::HashSet, io::stdout, time::Instant};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 #[derive(Clone, PartialEq)]
 pub enum AppMode {
@@ -20,13 +52,29 @@ pub enum AppMode {
 pub enum AppPopUp {
     TaskEditor,
     CategoryEditor,
+    Rescheduler,
+    DependencyEditor,
+    Stats,
+    Focus,
+    ConflictResolver,
+    History,
+    Messages,
+    Shifter,
+    CategoryPicker,
+    QuickAdd,
+    CommandLine,
+    CommandPalette,
 }
 
+// Check the text of a TextBox has to pass for an edit to go through
+pub type Verify = fn(&str) -> bool;
+
 #[derive(Clone, Default)]
 pub struct TextBox {
     pub text: String,
-    pub index: usize,
-    pub max_length: usize,
+    pub index: usize, // cursor as a byte offset, always at the start of a grapheme
+    pub max_length: usize, // in graphemes
+    pub scroll: usize, // byte offset of the first grapheme shown, kept up to date by view
 }
 
 impl TextBox {
@@ -36,19 +84,169 @@ impl TextBox {
             ..Default::default()
         }
     }
+
+    // Edits the text for a key press, only allowing insertions that pass verify
+    pub fn handle_key(&mut self, key: &Key, verify: Verify) {
+        match key {
+            Key::Number(c) | Key::Char(c) => {
+                let mut proposed_text = self.text.clone();
+                proposed_text.insert(self.index, *c);
+
+                if proposed_text.graphemes(true).count() <= self.max_length
+                    && verify(&proposed_text)
+                {
+                    self.text = proposed_text;
+                    self.index += c.len_utf8();
+                }
+            }
+            Key::Left => self.index = self.previous_grapheme(),
+            Key::Right => self.index = self.next_grapheme(),
+            Key::Chord(m, Code::Left) if m.ctrl => self.index = self.previous_word(),
+            Key::Chord(m, Code::Right) if m.ctrl => self.index = self.next_word(),
+            Key::Home => self.index = 0,
+            Key::End => self.index = self.text.len(),
+            Key::Backspace => self.delete_back_to(self.previous_grapheme()),
+            Key::Ctrl('w') => self.delete_back_to(self.previous_word()),
+            Key::Delete => {
+                self.text
+                    .replace_range(self.index..self.next_grapheme(), "");
+            }
+            _ => {}
+        }
+    }
+
+    // Types out pasted text one character at a time, with line breaks and tabs becoming spaces
+    // since the box is a single line
+    pub fn paste(&mut self, text: &str, verify: Verify) {
+        for c in text.trim_end().replace("\r\n", "\n").chars() {
+            if c.is_whitespace() {
+                self.handle_key(&Key::Char(' '), verify);
+            } else if !c.is_control() {
+                self.handle_key(&Key::Char(c), verify);
+            }
+        }
+    }
+
+    fn delete_back_to(&mut self, start: usize) {
+        self.text.replace_range(start..self.index, "");
+        self.index = start;
+    }
+
+    fn previous_grapheme(&self) -> usize {
+        self.text[..self.index]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    fn next_grapheme(&self) -> usize {
+        self.text[self.index..]
+            .graphemes(true)
+            .next()
+            .map_or(self.index, |g| self.index + g.len())
+    }
+
+    // Start of the word before the cursor, skipping any whitespace in between
+    fn previous_word(&self) -> usize {
+        self.text[..self.index]
+            .split_word_bound_indices()
+            .rev()
+            .find(|(_, w)| !w.trim().is_empty())
+            .map_or(0, |(i, _)| i)
+    }
+
+    // End of the word after the cursor, skipping any whitespace in between
+    fn next_word(&self) -> usize {
+        self.text[self.index..]
+            .split_word_bound_indices()
+            .find(|(_, w)| !w.trim().is_empty())
+            .map_or(self.text.len(), |(i, w)| self.index + i + w.len())
+    }
+
+    // The part of the text that fits in width columns, scrolling just enough to keep the cursor
+    // in view
+    pub fn view(&mut self, width: u16) -> String {
+        let width = width as usize;
+        if self.scroll > self.index || !self.text.is_char_boundary(self.scroll) {
+            self.scroll = 0;
+        }
+
+        // The cursor takes up a column of its own at the end of the text
+        while self.scroll < self.index && self.text[self.scroll..self.index].width() >= width {
+            self.scroll += self.text[self.scroll..]
+                .graphemes(true)
+                .next()
+                .unwrap()
+                .len();
+        }
+        while let Some((previous, _)) = self.text[..self.scroll].grapheme_indices(true).next_back()
+        {
+            if self.text[previous..].width() >= width {
+                break;
+            }
+            self.scroll = previous;
+        }
+
+        let mut shown_width = 0;
+        self.text[self.scroll..]
+            .graphemes(true)
+            .take_while(|g| {
+                shown_width += g.width();
+                shown_width <= width
+            })
+            .collect()
+    }
+
+    // Column of the cursor within what view last showed
+    pub fn cursor_column(&self) -> u16 {
+        let start = if self.scroll <= self.index && self.text.is_char_boundary(self.scroll) {
+            self.scroll
+        } else {
+            0
+        };
+        self.text[start..self.index].width() as u16
+    }
 }
 
-#[derive(PartialEq)]
+pub fn parse_date(year: &TextBox, month: &TextBox, date: &TextBox) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(
+        year.text.parse::<i32>().ok()?,
+        month.text.parse::<u32>().ok()?,
+        date.text.parse::<u32>().ok()?,
+    )
+}
+
+// Blank fields mean no date, so only partially filled or impossible dates are rejected
+pub fn parse_optional_date(
+    year: &TextBox,
+    month: &TextBox,
+    date: &TextBox,
+) -> Option<Option<NaiveDate>> {
+    if year.text.is_empty() && month.text.is_empty() && date.text.is_empty() {
+        Some(None)
+    } else {
+        parse_date(year, month, date).map(Some)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum SelectedField {
     Name,
     Year,
     Month,
     Date,
+    ScheduledYear,
+    ScheduledMonth,
+    ScheduledDate,
+    Estimate,
     Category,
 }
 
 pub struct App {
     io_tx: tokio::sync::mpsc::Sender<IOEvent>,
+    pub config: Config,
+    pub keymap: Keymap,
+    pub theme: Theme,
 
     pub mode: AppMode,
     pub pop_up: Option<AppPopUp>,
@@ -58,11 +256,17 @@ pub struct App {
     pub year_edit: TextBox,
     pub month_edit: TextBox,
     pub date_edit: TextBox,
+    pub scheduled_year_edit: TextBox,
+    pub scheduled_month_edit: TextBox,
+    pub scheduled_date_edit: TextBox,
+    pub estimate_edit: TextBox,
     pub category_edit_state: ListState,
+    pub search_edit: TextBox,
+    pub dependency_list_state: ListState,
     pub editing_task: bool,
     pub editing_category: bool,
 
-    pub status_text: String,
+    pub status: StatusLog,
     pub keybind_hints: String,
 
     pub task_list: TaskList,
@@ -70,6 +274,46 @@ pub struct App {
 
     pub categories: Vec<Category>,
     pub category_list_state: ListState,
+
+    pub current_date: NaiveDate,
+    pub last_refresh: Instant,
+
+    pub timer: Option<Timer>,
+    pub pomodoro: Option<Pomodoro>,
+    pub stats: Stats,
+    pub stats_list_state: ListState,
+
+    pub conflicts: Vec<Conflict>,
+    pub conflicts_dismissed: bool, // left for later with esc, so they don't open again by themselves
+    pub conflict_field_state: ListState,
+
+    pub history: Vec<TaskEvent>,
+    pub history_list_state: ListState,
+    pub messages_list_state: ListState,
+
+    pub undo_history: UndoHistory,
+
+    pub selection: HashSet<i32>,
+    pub selection_anchor: Option<i32>, // start of the range being selected with [v]
+    pub bulk_ids: Vec<i32>,            // tasks the open bulk pop up applies to
+    pub shift_edit: TextBox,
+    pub quick_add_edit: TextBox,
+    pub command_edit: TextBox,
+    pub command_history: Vec<String>,
+    pub command_history_index: Option<usize>, // entry being recalled with up/down
+    pub palette_edit: TextBox,
+    pub palette_list_state: ListState,
+    pub recent_actions: Vec<Action>, // run from the palette, most recent first
+    pub pending_keys: Vec<Key>,      // start of a key sequence, e.g. the first g of gg
+    pub pending_keys_at: Instant,
+    pub mouse_capture: bool,
+    pub click_targets: Vec<(Rect, ClickTarget)>, // recorded while drawing, topmost last
+    pub last_click: Option<(Instant, ClickTarget)>,
+    pub help: bool, // key binding overlay, drawn over any pop up
+    pub help_scroll: usize,
+    pub show_details: bool,
+    pub task_timestamps: Option<TaskTimestamps>, // of the task in the detail pane
+    pub timestamps_requested: Option<(i32, i32)>, // task id and version last sent to be loaded
 }
 
 #[derive(PartialEq)]
@@ -79,9 +323,17 @@ pub enum AppReturn {
 }
 
 impl App {
-    pub fn new(io_tx: tokio::sync::mpsc::Sender<IOEvent>) -> Self {
+    pub fn new(
+        io_tx: tokio::sync::mpsc::Sender<IOEvent>,
+        config: Config,
+        keymap: Keymap,
+        theme: Theme,
+    ) -> Self {
         Self {
             io_tx,
+            config,
+            keymap,
+            theme,
             mode: AppMode::Upcoming,
             pop_up: None,
             task_edit_field: SelectedField::Name,
@@ -89,15 +341,55 @@ impl App {
             year_edit: TextBox::new(4),
             month_edit: TextBox::new(2),
             date_edit: TextBox::new(2),
+            scheduled_year_edit: TextBox::new(4),
+            scheduled_month_edit: TextBox::new(2),
+            scheduled_date_edit: TextBox::new(2),
+            estimate_edit: TextBox::new(6),
             category_edit_state: ListState::default(),
+            search_edit: TextBox::new(36),
+            dependency_list_state: ListState::default(),
             editing_task: false,
             editing_category: false, //TODO: NEED TO SET THIS PROPERLy
-            status_text: "".to_string(),
+            status: StatusLog::default(),
             keybind_hints: "".to_string(),
             task_list: TaskList::new(),
             task_list_state: ListState::default().with_selected(Some(0)),
             categories: Vec::new(),
             category_list_state: ListState::default().with_selected(Some(0)),
+            current_date: Local::now().date_naive(),
+            last_refresh: Instant::now(),
+            timer: None,
+            pomodoro: None,
+            stats: Stats::default(),
+            stats_list_state: ListState::default(),
+            conflicts: Vec::new(),
+            conflicts_dismissed: false,
+            conflict_field_state: ListState::default(),
+            history: Vec::new(),
+            history_list_state: ListState::default(),
+            messages_list_state: ListState::default(),
+            undo_history: UndoHistory::default(),
+            selection: HashSet::new(),
+            selection_anchor: None,
+            bulk_ids: Vec::new(),
+            shift_edit: TextBox::new(4),
+            quick_add_edit: TextBox::new(80),
+            command_edit: TextBox::new(80),
+            command_history: Vec::new(),
+            command_history_index: None,
+            palette_edit: TextBox::new(40),
+            palette_list_state: ListState::default(),
+            recent_actions: Vec::new(),
+            pending_keys: Vec::new(),
+            pending_keys_at: Instant::now(),
+            mouse_capture: false,
+            click_targets: Vec::new(),
+            last_click: None,
+            help: false,
+            help_scroll: 0,
+            show_details: false,
+            task_timestamps: None,
+            timestamps_requested: None,
         }
     }
 
@@ -105,55 +397,320 @@ impl App {
     pub async fn initialize(&mut self) {
         self.dispatch(IOEvent::LoadData).await;
         self.switch_mode(AppMode::Upcoming);
+        self.set_mouse_capture(self.config.mouse);
+        self.show_details = self.config.detail_pane;
     }
 
     pub async fn do_action(&mut self, key: Key) -> AppReturn {
-        if self.pop_up.is_none() {
-            match key {
-                Key::Number('1') => self.switch_mode(AppMode::Upcoming),
-                Key::Number('2') => self.switch_mode(AppMode::Categories),
-                _ => match self.mode {
-                    AppMode::Upcoming => match key {
-                        Key::Char('e') => {
-                            if let TaskDate::Task(t) = &self.task_list.current_taskdate {
-                                if !t.completed {
-                                    self.editing_task = true;
-                                    self.enable_pop_up(AppPopUp::TaskEditor);
-                                }
-                            }
-                            AppReturn::Continue
-                        }
-                        Key::Char('a') => {
-                            self.editing_task = false;
-                            self.enable_pop_up(AppPopUp::TaskEditor);
-                            AppReturn::Continue
-                        }
-                        _ => upcoming::do_action(self, key).await,
-                    },
-                    AppMode::Categories => match key {
-                        Key::Char('e') => {
-                            self.editing_category = true;
-                            self.enable_pop_up(AppPopUp::CategoryEditor);
-                            AppReturn::Continue
-                        }
-                        Key::Char('a') => {
-                            self.editing_category = false;
-                            self.enable_pop_up(AppPopUp::CategoryEditor);
-                            AppReturn::Continue
-                        }
-                        _ => categories::do_action(self, key).await,
-                    },
-                },
-            }
+        if self.help {
+            help::do_action(self, key).await
+        } else if self.pop_up.is_none() {
+            self.pending_keys.push(key);
+            self.pending_keys_at = Instant::now();
+            self.run_pending_keys(false).await
+        } else if self.keymap.action_for(&key, self.pop_up_contexts()) == Some(Action::Help) {
+            help::initialize(self)
         } else {
             let p = self.pop_up.as_ref().unwrap();
             match p {
                 AppPopUp::TaskEditor => task_editor::do_action(self, key).await,
                 AppPopUp::CategoryEditor => category_editor::do_action(self, key).await,
+                AppPopUp::Rescheduler => rescheduler::do_action(self, key).await,
+                AppPopUp::DependencyEditor => dependency_editor::do_action(self, key).await,
+                AppPopUp::Stats => stats::do_action(self, key).await,
+                AppPopUp::Focus => focus::do_action(self, key).await,
+                AppPopUp::ConflictResolver => conflict_resolver::do_action(self, key).await,
+                AppPopUp::History => history::do_action(self, key).await,
+                AppPopUp::Messages => messages::do_action(self, key).await,
+                AppPopUp::Shifter => shifter::do_action(self, key).await,
+                AppPopUp::CategoryPicker => category_picker::do_action(self, key).await,
+                AppPopUp::QuickAdd => quick_add_bar::do_action(self, key).await,
+                AppPopUp::CommandLine => command_line::do_action(self, key).await,
+                AppPopUp::CommandPalette => command_palette::do_action(self, key).await,
             }
         }
     }
 
+    pub async fn do_mouse_action(&mut self, mouse: Mouse) -> AppReturn {
+        mouse::do_action(self, mouse).await
+    }
+
+    // Capturing the mouse keeps the terminal from selecting text, so it can be turned off
+    pub fn set_mouse_capture(&mut self, on: bool) {
+        let result = if on {
+            crossterm::execute!(stdout(), EnableMouseCapture)
+        } else {
+            crossterm::execute!(stdout(), DisableMouseCapture)
+        };
+
+        match result {
+            Ok(()) => self.mouse_capture = on,
+            Err(e) => self
+                .status
+                .error(format!("couldn't change mouse capture: {}", e)),
+        }
+    }
+
+    // Pasted text goes into the text box of the pop up, if it has one
+    pub fn paste(&mut self, text: &str) -> AppReturn {
+        match self.pop_up {
+            _ if self.help => (),
+            Some(AppPopUp::TaskEditor) => task_editor::paste(self, text),
+            Some(AppPopUp::CategoryEditor) => category_editor::paste(self, text),
+            Some(AppPopUp::Rescheduler) => rescheduler::paste(self, text),
+            Some(AppPopUp::DependencyEditor) => dependency_editor::paste(self, text),
+            Some(AppPopUp::Shifter) => shifter::paste(self, text),
+            Some(AppPopUp::QuickAdd) => quick_add_bar::paste(self, text),
+            Some(AppPopUp::CommandLine) => command_line::paste(self, text),
+            Some(AppPopUp::CommandPalette) => command_palette::paste(self, text),
+            _ => (),
+        }
+
+        AppReturn::Continue
+    }
+
+    pub async fn run_action(&mut self, action: Action) -> AppReturn {
+        match action {
+            Action::ShowUpcoming => self.switch_mode(AppMode::Upcoming),
+            Action::ShowCategories => self.switch_mode(AppMode::Categories),
+            Action::NextView => match self.mode {
+                AppMode::Upcoming => self.switch_mode(AppMode::Categories),
+                AppMode::Categories => self.switch_mode(AppMode::Upcoming),
+            },
+            Action::Stats => {
+                self.dispatch(IOEvent::LoadStats).await;
+                self.enable_pop_up(AppPopUp::Stats);
+                AppReturn::Continue
+            }
+            Action::ToggleDetails => {
+                self.show_details = !self.show_details;
+                AppReturn::Continue
+            }
+            Action::ResolveConflicts => {
+                if self.conflicts.is_empty() {
+                    self.status.info("no edit conflicts to resolve");
+                } else {
+                    self.conflicts_dismissed = false;
+                    self.enable_pop_up(AppPopUp::ConflictResolver);
+                }
+                AppReturn::Continue
+            }
+            Action::Messages => {
+                self.enable_pop_up(AppPopUp::Messages);
+                AppReturn::Continue
+            }
+            Action::Undo => {
+                self.undo().await;
+                AppReturn::Continue
+            }
+            Action::Redo => {
+                self.redo().await;
+                AppReturn::Continue
+            }
+            Action::CommandLine => {
+                self.enable_pop_up(AppPopUp::CommandLine);
+                AppReturn::Continue
+            }
+            Action::CommandPalette => {
+                self.enable_pop_up(AppPopUp::CommandPalette);
+                AppReturn::Continue
+            }
+            Action::ToggleMouse => {
+                self.set_mouse_capture(!self.mouse_capture);
+                self.status.info(if self.mouse_capture {
+                    "mouse on"
+                } else {
+                    "mouse off, text can be selected"
+                });
+                AppReturn::Continue
+            }
+            Action::Help => help::initialize(self),
+            Action::Quit => AppReturn::Quit,
+            Action::ToggleSelect
+            | Action::SelectRange
+            | Action::SelectGroup
+            | Action::CompleteSelected
+            | Action::DeleteSelected
+            | Action::MoveSelected
+            | Action::ShiftSelected
+            | Action::RecategorizeSelected
+            | Action::ClearSelection
+            | Action::YankText
+            | Action::YankMarkdown => selection::do_action(self, action).await,
+            Action::EditTask => {
+                if let TaskDate::Task(t) = &self.task_list.current_taskdate {
+                    if !t.completed {
+                        self.editing_task = true;
+                        self.enable_pop_up(AppPopUp::TaskEditor);
+                    }
+                }
+                AppReturn::Continue
+            }
+            Action::AddTask => {
+                self.editing_task = false;
+                self.enable_pop_up(AppPopUp::TaskEditor);
+                AppReturn::Continue
+            }
+            Action::QuickAdd => {
+                self.enable_pop_up(AppPopUp::QuickAdd);
+                AppReturn::Continue
+            }
+            Action::EditBlockers => {
+                if let TaskDate::Task(t) = &self.task_list.current_taskdate {
+                    if !t.completed {
+                        self.enable_pop_up(AppPopUp::DependencyEditor);
+                    }
+                }
+                AppReturn::Continue
+            }
+            Action::Focus => {
+                if let TaskDate::Task(t) = &self.task_list.current_taskdate {
+                    if !t.completed {
+                        self.enable_pop_up(AppPopUp::Focus);
+                    }
+                }
+                AppReturn::Continue
+            }
+            Action::History => {
+                if let TaskDate::Task(t) = &self.task_list.current_taskdate {
+                    let task_id = t.id;
+                    self.enable_pop_up(AppPopUp::History);
+                    self.dispatch(IOEvent::LoadHistory(task_id)).await;
+                }
+                AppReturn::Continue
+            }
+            Action::RescheduleOverdue => {
+                if self.task_list.overdue_count() > 0 {
+                    self.bulk_ids = self.task_list.overdue_ids();
+                    self.enable_pop_up(AppPopUp::Rescheduler);
+                }
+                AppReturn::Continue
+            }
+            Action::EditCategory => {
+                self.editing_category = true;
+                self.enable_pop_up(AppPopUp::CategoryEditor);
+                AppReturn::Continue
+            }
+            Action::AddCategory => {
+                self.editing_category = false;
+                self.enable_pop_up(AppPopUp::CategoryEditor);
+                AppReturn::Continue
+            }
+            _ => match self.mode {
+                AppMode::Upcoming => upcoming::do_action(self, action).await,
+                AppMode::Categories => categories::do_action(self, action).await,
+            },
+        }
+    }
+
+    // Runs the binding for the keys pressed so far, waiting for more while they could still be the
+    // start of a longer sequence, unless it's timed out
+    async fn run_pending_keys(&mut self, timed_out: bool) -> AppReturn {
+        let contexts = self.active_contexts();
+
+        loop {
+            match self.keymap.resolve(&self.pending_keys, &contexts) {
+                KeyMatch::Partial(_) if !timed_out => return AppReturn::Continue,
+                KeyMatch::Full(action) | KeyMatch::Partial(Some(action)) => {
+                    self.pending_keys.clear();
+                    return self.run_action(action).await;
+                }
+                // A key that breaks off a sequence is tried again on its own
+                _ if self.pending_keys.len() > 1 => {
+                    self.pending_keys.drain(..self.pending_keys.len() - 1);
+                }
+                _ => {
+                    self.pending_keys.clear();
+                    return AppReturn::Continue;
+                }
+            }
+        }
+    }
+
+    // Binding contexts that apply right now, in order of precedence
+    pub fn active_contexts(&self) -> Vec<Context> {
+        let mut contexts = Vec::new();
+        if !self.selection.is_empty() || self.selection_anchor.is_some() {
+            contexts.push(Context::Selection);
+        }
+        contexts.push(match self.mode {
+            AppMode::Upcoming => Context::Upcoming,
+            AppMode::Categories => Context::Categories,
+        });
+        contexts.push(Context::Global);
+        contexts
+    }
+
+    // Binding contexts of the open pop up, in order of precedence
+    pub fn pop_up_contexts(&self) -> &'static [Context] {
+        match self.pop_up {
+            Some(AppPopUp::TaskEditor) => task_editor::CONTEXTS,
+            Some(AppPopUp::CategoryEditor) => category_editor::CONTEXTS,
+            Some(AppPopUp::Rescheduler) => rescheduler::CONTEXTS,
+            Some(AppPopUp::DependencyEditor) => dependency_editor::CONTEXTS,
+            Some(AppPopUp::Stats) => stats::CONTEXTS,
+            Some(AppPopUp::Focus) => focus::CONTEXTS,
+            Some(AppPopUp::ConflictResolver) => conflict_resolver::CONTEXTS,
+            Some(AppPopUp::History) => history::CONTEXTS,
+            Some(AppPopUp::Messages) => messages::CONTEXTS,
+            Some(AppPopUp::Shifter) => shifter::CONTEXTS,
+            Some(AppPopUp::CategoryPicker) => category_picker::CONTEXTS,
+            Some(AppPopUp::QuickAdd) => quick_add_bar::CONTEXTS,
+            Some(AppPopUp::CommandLine) => command_line::CONTEXTS,
+            Some(AppPopUp::CommandPalette) => command_palette::CONTEXTS,
+            None => &[],
+        }
+    }
+
+    // Everything the keys do in the open pop up, or the view if there isn't one, under the
+    // headings of Action::GROUPS
+    pub fn help_groups(&self) -> Vec<(&'static str, Vec<Binding>)> {
+        let actions = if self.pop_up.is_some() {
+            self.keymap.available_actions(self.pop_up_contexts())
+        } else {
+            self.keymap.available_actions(&self.active_contexts())
+        };
+
+        Action::GROUPS
+            .iter()
+            .map(|group| {
+                let in_group: Vec<Binding> = actions
+                    .iter()
+                    .filter(|(action, _)| action.group() == *group)
+                    .cloned()
+                    .collect();
+                (*group, in_group)
+            })
+            .filter(|(_, in_group)| !in_group.is_empty())
+            .collect()
+    }
+
+    // Actions offered by the command palette, names containing the search first, then the best
+    // fuzzy matches, with recently used ones ahead of the rest
+    pub fn palette_actions(&self) -> Vec<Binding> {
+        let search = self.palette_edit.text.to_lowercase();
+        let recency = |action: &Action| {
+            self.recent_actions
+                .iter()
+                .position(|a| a == action)
+                .unwrap_or(self.recent_actions.len())
+        };
+
+        let mut actions: Vec<(bool, i64, Binding)> = self
+            .keymap
+            .available_actions(&self.active_contexts())
+            .into_iter()
+            .filter(|(action, _)| *action != Action::CommandPalette)
+            .filter_map(|a| {
+                let contains = a.0.name().to_lowercase().contains(&search);
+                fuzzy::score(&search, a.0.name()).map(|score| (contains, score, a))
+            })
+            .collect();
+        actions.sort_by_key(|(contains, score, (action, _))| (!contains, -score, recency(action)));
+
+        actions.into_iter().map(|(_, _, a)| a).collect()
+    }
+
     fn switch_mode(&mut self, mode: AppMode) -> AppReturn {
         self.task_list.tasks.retain(|t| !t.completed); // Purge completed tasks when switching views
 
@@ -178,9 +735,46 @@ impl App {
             AppPopUp::CategoryEditor => {
                 category_editor::initialize(self);
             }
+            AppPopUp::Rescheduler => {
+                rescheduler::initialize(self);
+            }
+            AppPopUp::DependencyEditor => {
+                dependency_editor::initialize(self);
+            }
+            AppPopUp::Stats => {
+                stats::initialize(self);
+            }
+            AppPopUp::Focus => {
+                focus::initialize(self);
+            }
+            AppPopUp::ConflictResolver => {
+                conflict_resolver::initialize(self);
+            }
+            AppPopUp::History => {
+                history::initialize(self);
+            }
+            AppPopUp::Messages => {
+                messages::initialize(self);
+            }
+            AppPopUp::Shifter => {
+                shifter::initialize(self);
+            }
+            AppPopUp::CategoryPicker => {
+                category_picker::initialize(self);
+            }
+            AppPopUp::QuickAdd => {
+                quick_add_bar::initialize(self);
+            }
+            AppPopUp::CommandLine => {
+                command_line::initialize(self);
+            }
+            AppPopUp::CommandPalette => {
+                command_palette::initialize(self);
+            }
         }
 
         self.pop_up = Some(pop_up);
+        self.pending_keys.clear();
     }
 
     pub fn disable_pop_up(&mut self) {
@@ -188,10 +782,208 @@ impl App {
         self.switch_mode(self.mode.clone());
     }
 
-    pub async fn update_on_tick(&self) -> AppReturn {
+    pub async fn update_on_tick(&mut self) -> AppReturn {
+        focus::update_on_tick(self).await;
+
+        let timeout = self.config.key_sequence_timeout_ms as u128;
+        let sequence_timed_out =
+            !self.pending_keys.is_empty() && self.pending_keys_at.elapsed().as_millis() >= timeout;
+        if sequence_timed_out && self.run_pending_keys(true).await == AppReturn::Quit {
+            return AppReturn::Quit;
+        }
+
+        // Rejected edits are resolved one at a time, as soon as nothing else is open
+        if self.pop_up.is_none() && !self.conflicts.is_empty() && !self.conflicts_dismissed {
+            self.enable_pop_up(AppPopUp::ConflictResolver);
+        }
+
+        // Reload when the day rolls over so "Today" moves, and periodically to pick up changes made
+        // by other clients. Pop ups stay open through it, with the cursor kept on their task.
+        let today = Local::now().date_naive();
+        let refresh_interval = self.config.refresh_interval_seconds;
+        let refresh_due =
+            refresh_interval > 0 && self.last_refresh.elapsed().as_secs() >= refresh_interval;
+
+        if today != self.current_date || refresh_due {
+            self.current_date = today;
+            self.last_refresh = Instant::now();
+            self.dispatch(IOEvent::RefreshData).await;
+        }
+
+        // The detail pane's timestamps come from the event log, so they're loaded for whichever task
+        // it's showing, again after every edit to it
+        if self.show_details && self.mode == AppMode::Upcoming {
+            let selected = match &self.task_list.current_taskdate {
+                TaskDate::Task(t) => Some((t.id, t.version)),
+                _ => None,
+            };
+            if let Some((id, _)) = selected.filter(|s| self.timestamps_requested != Some(*s)) {
+                self.timestamps_requested = selected;
+                self.dispatch(IOEvent::LoadTimestamps(id)).await;
+            }
+        }
+
         AppReturn::Continue
     }
 
+    pub async fn undo(&mut self) {
+        let Some(UndoEntry {
+            description, undo, ..
+        }) = self.undo_history.undo()
+        else {
+            self.status.warn("nothing to undo");
+            return;
+        };
+        let (description, events) = (description.clone(), undo.clone());
+
+        self.replay(events).await;
+        self.dispatch(IOEvent::Status(format!("undid {}", description)))
+            .await;
+    }
+
+    pub async fn redo(&mut self) {
+        let Some(UndoEntry {
+            description, redo, ..
+        }) = self.undo_history.redo()
+        else {
+            self.status.warn("nothing to redo");
+            return;
+        };
+        let (description, events) = (description.clone(), redo.clone());
+
+        self.replay(events).await;
+        self.dispatch(IOEvent::Status(format!("redid {}", description)))
+            .await;
+    }
+
+    // Applies the events to the local lists the same way the original action did, then sends them
+    async fn replay(&mut self, events: Vec<IOEvent>) {
+        let selected_task = self.selected_task_id();
+
+        for event in events {
+            match &event {
+                IOEvent::UpdateTask(t) => self.task_list.upsert(t.clone()),
+                IOEvent::UpdateTasks(tasks) | IOEvent::RestoreTasks(tasks) => {
+                    tasks.iter().for_each(|t| self.task_list.upsert(t.clone()));
+                }
+                IOEvent::DeleteTasks(tasks) => {
+                    let ids: Vec<i32> = tasks.iter().map(|t| t.id).collect();
+                    ids.iter().for_each(|id| self.task_list.remove(*id));
+                    if self
+                        .timer
+                        .as_ref()
+                        .is_some_and(|t| ids.contains(&t.task_id))
+                    {
+                        self.timer = None;
+                    }
+                }
+                IOEvent::AddDependency(blocker, blocked) => {
+                    self.task_list.dependencies.push((*blocker, *blocked));
+                }
+                IOEvent::UpdateCategory(c) => {
+                    self.task_list
+                        .tasks
+                        .iter_mut()
+                        .filter(|t| t.category.id == c.id)
+                        .for_each(|t| t.category = c.clone());
+                    if let Some(existing) = self.categories.iter_mut().find(|e| e.id == c.id) {
+                        *existing = c.clone();
+                    }
+                }
+                _ => (),
+            }
+            self.dispatch(event).await;
+        }
+
+        self.task_list.tasks.sort();
+        if let Some(selected_id) = selected_task {
+            self.reselect_task(selected_id);
+        }
+    }
+
+    // Tasks picked with [space]/[V], plus the range being selected with [v] if there is one
+    pub fn selected_ids(&self) -> HashSet<i32> {
+        let mut ids = self.selection.clone();
+        match (self.selection_anchor, self.selected_task_id()) {
+            (Some(anchor), Some(current)) => {
+                ids.extend(self.task_list.ids_between(anchor, current))
+            }
+            (Some(anchor), None) => {
+                ids.insert(anchor);
+            }
+            _ => (),
+        }
+        ids
+    }
+
+    // Task described by a quick-add line, with the date and category defaulting to those under
+    // the cursor like in the task editor
+    pub fn quick_add_task(&self, text: &str) -> Result<Task, String> {
+        let today = Local::now().date_naive();
+        let parsed = quick_add::parse(text, &self.categories, today);
+
+        if !parsed.errors.is_empty() {
+            return Err(format!("couldn't read {}", parsed.errors.join(", ")));
+        }
+        if parsed.name.is_empty() {
+            return Err("task needs a name".to_string());
+        }
+
+        let (default_date, default_category) = match &self.task_list.current_taskdate {
+            TaskDate::Task(t) => (t.planned_date(), Some(t.category.clone())),
+            TaskDate::Date(d) => (*d, self.categories.first().cloned()),
+            TaskDate::Overdue(_) => (today, self.categories.first().cloned()),
+        };
+
+        Ok(Task {
+            due_date: parsed.date.unwrap_or(default_date),
+            name: parsed.name,
+            completed: false,
+            category: parsed
+                .category
+                .or(default_category)
+                .ok_or("no categories to add the task to")?,
+            id: -1,
+            version: 1,
+            scheduled_date: None,
+            estimate_minutes: parsed.estimate_minutes,
+            priority: parsed.priority,
+            tags: parsed.tags,
+        })
+    }
+
+    pub fn selected_task_id(&self) -> Option<i32> {
+        match &self.task_list.current_taskdate {
+            TaskDate::Task(t) => Some(t.id),
+            _ => None,
+        }
+    }
+
+    // Looked up by id, since the list can change under an open pop up
+    pub fn selected_task(&self) -> Option<&Task> {
+        let id = self.selected_task_id()?;
+        self.task_list.tasks.iter().find(|t| t.id == id)
+    }
+
+    // The category editor works on the highlighted category when renaming
+    pub fn renaming_category(&self) -> bool {
+        self.editing_category && matches!(self.pop_up, Some(AppPopUp::CategoryEditor))
+    }
+
+    // For pop ups whose task or category another client deleted while they were open
+    pub fn close_stale_pop_up(&mut self, what: &str) {
+        self.disable_pop_up();
+        self.status
+            .warn(format!("{} was deleted by another client", what));
+    }
+
+    // Moves the cursor back onto a task after rows were added or removed around it
+    pub fn reselect_task(&mut self, id: i32) {
+        if let Some(position) = self.task_list.position_of(id) {
+            self.task_list_state.select(Some(position));
+        }
+    }
+
     // Dispatch database work to seperate thread
     pub async fn dispatch(&self, action: IOEvent) {
         if self.io_tx.send(action).await.is_err() {
@@ -199,3 +991,122 @@ impl App {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key::Modifiers;
+
+    fn typed(text: &str, max_length: usize) -> TextBox {
+        let mut text_box = TextBox::new(max_length);
+        for c in text.chars() {
+            text_box.handle_key(&Key::Char(c), |_| true);
+        }
+        text_box
+    }
+
+    fn press(text_box: &mut TextBox, keys: &[Key]) {
+        for key in keys {
+            text_box.handle_key(key, |_| true);
+        }
+    }
+
+    fn ctrl(code: Code) -> Key {
+        Key::Chord(
+            Modifiers {
+                ctrl: true,
+                ..Default::default()
+            },
+            code,
+        )
+    }
+
+    #[test]
+    fn moves_over_whole_graphemes() {
+        // e and a combining accent, then a thumbs up with a skin tone
+        let mut text_box = typed("e\u{301}👍🏽x", 10);
+        assert_eq!(text_box.index, text_box.text.len());
+
+        press(&mut text_box, &[Key::Left, Key::Left]);
+        assert_eq!(text_box.index, "e\u{301}".len());
+        press(&mut text_box, &[Key::Left, Key::Left]);
+        assert_eq!(text_box.index, 0);
+        press(&mut text_box, &[Key::Right]);
+        assert_eq!(text_box.index, "e\u{301}".len());
+        press(&mut text_box, &[Key::End, Key::Right]);
+        assert_eq!(text_box.index, text_box.text.len());
+    }
+
+    #[test]
+    fn deletes_whole_graphemes() {
+        let mut text_box = typed("e\u{301}👍🏽x", 10);
+
+        press(&mut text_box, &[Key::Left, Key::Backspace]);
+        assert_eq!(text_box.text, "e\u{301}x");
+        assert_eq!(text_box.index, "e\u{301}".len());
+
+        press(&mut text_box, &[Key::Home, Key::Delete]);
+        assert_eq!(text_box.text, "x");
+        assert_eq!(text_box.index, 0);
+
+        press(&mut text_box, &[Key::Backspace, Key::End, Key::Delete]);
+        assert_eq!(text_box.text, "x");
+    }
+
+    #[test]
+    fn inserts_at_the_cursor() {
+        let mut text_box = typed("ac", 10);
+        press(&mut text_box, &[Key::Left, Key::Char('b')]);
+
+        assert_eq!(text_box.text, "abc");
+        assert_eq!(text_box.index, 2);
+    }
+
+    #[test]
+    fn limits_length_in_graphemes() {
+        let text_box = typed("ae\u{301}bc", 3);
+        assert_eq!(text_box.text, "ae\u{301}b");
+
+        let mut digits = TextBox::new(4);
+        for c in "1a2".chars() {
+            digits.handle_key(&Key::Char(c), |t| t.chars().all(|c| c.is_ascii_digit()));
+        }
+        assert_eq!(digits.text, "12");
+    }
+
+    #[test]
+    fn moves_and_deletes_by_word() {
+        let mut text_box = typed("read ch 4 ", 20);
+
+        press(&mut text_box, &[ctrl(Code::Left)]);
+        assert_eq!(text_box.index, 8);
+        press(&mut text_box, &[ctrl(Code::Left), ctrl(Code::Right)]);
+        assert_eq!(text_box.index, 7);
+        press(&mut text_box, &[Key::Ctrl('w')]);
+        assert_eq!(text_box.text, "read  4 ");
+        press(&mut text_box, &[Key::Ctrl('w'), Key::Ctrl('w')]);
+        assert_eq!(text_box.text, " 4 ");
+        assert_eq!(text_box.index, 0);
+    }
+
+    #[test]
+    fn pastes_on_one_line() {
+        let mut text_box = TextBox::new(20);
+        text_box.paste("lab\r\nreport\tdue\u{7}\n\n", |_| true);
+
+        assert_eq!(text_box.text, "lab report due");
+    }
+
+    #[test]
+    fn scrolls_to_keep_the_cursor_in_view() {
+        let mut text_box = typed("日本語テキスト", 20);
+
+        // The cursor needs a column of its own past the last character
+        assert_eq!(text_box.view(6), "スト");
+        assert_eq!(text_box.cursor_column(), 4);
+
+        press(&mut text_box, &[Key::Home]);
+        assert_eq!(text_box.view(6), "日本語");
+        assert_eq!(text_box.cursor_column(), 0);
+    }
+}
@@ -1,70 +1,64 @@
 use crate::{
+    action::{Action, Context},
     app::{App, AppReturn, TextBox},
+    category::Category,
     database::IOEvent,
     key::Key,
+    undo::UndoEntry,
 };
 
+pub const CONTEXTS: &[Context] = &[Context::CategoryEditor, Context::PopUp];
+
 pub async fn do_action(app: &mut App, key: Key) -> AppReturn {
-    let name_text = &mut app.name_edit;
+    let action = app.keymap.action_for(&key, CONTEXTS);
+    if action.is_none() {
+        app.name_edit.handle_key(&key, |_x| true);
+    }
 
-    match key {
-        Key::Esc | Key::Ctrl('c') => {
+    match action {
+        Some(Action::Cancel) => {
             app.disable_pop_up();
         }
-        Key::Number(c) | Key::Char(c) => {
-            let mut proposed_text = name_text.text.clone();
-            proposed_text.insert(name_text.index, c);
-
-            if proposed_text.len() <= name_text.max_length {
-                *name_text = TextBox {
-                    text: proposed_text,
-                    index: name_text.index + 1,
-                    ..*name_text
-                };
-            }
-        }
-        Key::Left => {
-            if name_text.index > 0 {
-                name_text.index -= 1;
-            }
-        }
-        Key::Right => {
-            if name_text.index < name_text.text.len() {
-                name_text.index += 1;
-            }
-        }
-        Key::Backspace => {
-            if name_text.index > 0 {
-                name_text.text.remove(name_text.index - 1);
-                name_text.index -= 1;
+        Some(Action::Confirm) => {
+            if app.name_edit.text.is_empty() {
+                return AppReturn::Continue;
             }
-        }
-        Key::Enter => {
-            if !app.name_edit.text.is_empty() {
-                if app.editing_category {
-                    let editing_id = app.categories[app.category_list_state.selected().unwrap()].id;
-                    let new_name = app.name_edit.text.clone();
-
-                    app.categories[app.category_list_state.selected().unwrap()].name =
-                        new_name.clone();
 
-                    app.task_list
-                        .tasks
-                        .iter_mut()
-                        .filter(|t| t.category.id == editing_id)
-                        .for_each(|t| t.category.name = new_name.to_string());
+            if app.editing_category {
+                let Some(old_category) = app
+                    .categories
+                    .get(app.category_list_state.selected().unwrap_or(0))
+                    .cloned()
+                else {
+                    app.close_stale_pop_up("category");
+                    return AppReturn::Continue;
+                };
+                let new_category = Category {
+                    name: app.name_edit.text.clone(),
+                    ..old_category.clone()
+                };
 
-                    app.dispatch(IOEvent::UpdateCategory(
-                        app.categories[app.category_list_state.selected().unwrap()].clone(),
-                    ))
-                    .await;
-                } else {
-                    app.dispatch(IOEvent::CreateCategory(app.name_edit.text.clone()))
-                        .await;
+                if let Some(c) = app.categories.iter_mut().find(|c| c.id == new_category.id) {
+                    *c = new_category.clone();
                 }
+                app.task_list
+                    .tasks
+                    .iter_mut()
+                    .filter(|t| t.category.id == new_category.id)
+                    .for_each(|t| t.category = new_category.clone());
 
-                app.disable_pop_up();
+                app.undo_history.record(UndoEntry {
+                    description: format!("rename category {}", old_category.name),
+                    undo: vec![IOEvent::UpdateCategory(old_category)],
+                    redo: vec![IOEvent::UpdateCategory(new_category.clone())],
+                });
+                app.dispatch(IOEvent::UpdateCategory(new_category)).await;
+            } else {
+                app.dispatch(IOEvent::CreateCategory(app.name_edit.text.clone()))
+                    .await;
             }
+
+            app.disable_pop_up();
         }
         _ => {}
     }
@@ -72,6 +66,10 @@ pub async fn do_action(app: &mut App, key: Key) -> AppReturn {
     AppReturn::Continue
 }
 
+pub fn paste(app: &mut App, text: &str) {
+    app.name_edit.paste(text, |_x| true);
+}
+
 pub fn initialize(app: &mut App) -> AppReturn {
     let starting_name = if app.editing_category {
         app.categories[app.category_list_state.selected().unwrap()]
@@ -86,6 +84,7 @@ pub fn initialize(app: &mut App) -> AppReturn {
         text: starting_name,
         ..app.name_edit
     };
+    app.keybind_hints = app.keymap.hints(CONTEXTS);
 
     AppReturn::Continue
 }
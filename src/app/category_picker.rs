@@ -0,0 +1,60 @@
+use std::cmp::min;
+
+use crate::{
+    action::{Action, Context},
+    app::{App, AppReturn},
+    key::Key,
+};
+
+use super::selection;
+
+pub const CONTEXTS: &[Context] = &[Context::CategoryPicker, Context::PopUp];
+
+pub async fn do_action(app: &mut App, key: Key) -> AppReturn {
+    match app.keymap.action_for(&key, CONTEXTS) {
+        Some(Action::Cancel) => {
+            app.disable_pop_up();
+        }
+        Some(Action::ScrollDown) => {
+            app.category_edit_state.select(Some(min(
+                app.categories.len().saturating_sub(1),
+                app.category_edit_state.selected().unwrap_or(0) + 1,
+            )));
+        }
+        Some(Action::ScrollUp) => {
+            app.category_edit_state.select(Some(
+                app.category_edit_state
+                    .selected()
+                    .unwrap_or(0)
+                    .saturating_sub(1),
+            ));
+        }
+        Some(Action::Confirm) => {
+            let Some(category) = app
+                .categories
+                .get(app.category_edit_state.selected().unwrap_or(0))
+                .cloned()
+            else {
+                app.close_stale_pop_up("category");
+                return AppReturn::Continue;
+            };
+            let ids = app.bulk_ids.clone();
+            selection::update_tasks(app, &ids, &format!("move to {}", category.name), |t| {
+                t.category = category.clone()
+            })
+            .await;
+
+            app.disable_pop_up();
+        }
+        _ => (),
+    };
+
+    AppReturn::Continue
+}
+
+pub fn initialize(app: &mut App) -> AppReturn {
+    app.category_edit_state.select(Some(0));
+    app.keybind_hints = app.keymap.hints(CONTEXTS);
+
+    AppReturn::Continue
+}
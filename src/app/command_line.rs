@@ -0,0 +1,221 @@
+use chrono::Local;
+
+use crate::{
+    action::{Action, Context},
+    app::{App, AppMode, AppReturn, TextBox},
+    category::Category,
+    command::{self, Command, MoveTarget},
+    database::IOEvent,
+    key::Key,
+    task::add_days,
+    theme::Theme,
+    undo::UndoEntry,
+};
+
+use super::selection;
+
+pub const CONTEXTS: &[Context] = &[Context::CommandLine, Context::PopUp];
+
+pub async fn do_action(app: &mut App, key: Key) -> AppReturn {
+    let action = app.keymap.action_for(&key, CONTEXTS);
+    if action.is_none() {
+        app.command_edit.handle_key(&key, |_x| true);
+    }
+
+    match action {
+        Some(Action::Cancel) => {
+            app.disable_pop_up();
+        }
+        // Deleting the whole line closes it like in vim
+        None if key == Key::Backspace && app.command_edit.text.is_empty() => {
+            app.disable_pop_up();
+        }
+        Some(Action::CompleteCommand) => {
+            let category_names: Vec<String> =
+                app.categories.iter().map(|c| c.name.clone()).collect();
+            let (completed, candidates) =
+                command::complete(&app.command_edit.text, &category_names);
+
+            app.command_edit = TextBox {
+                index: completed.len(),
+                text: completed,
+                ..app.command_edit
+            };
+            if candidates.len() > 1 {
+                app.status.show(candidates.join("  "));
+            }
+        }
+        // Steps through previous commands, newest first
+        Some(Action::PreviousCommand | Action::NextCommand) => {
+            let history_len = app.command_history.len();
+            app.command_history_index = match (action, app.command_history_index) {
+                (Some(Action::PreviousCommand), None) if history_len > 0 => Some(history_len - 1),
+                (Some(Action::PreviousCommand), Some(i)) => Some(i.saturating_sub(1)),
+                (Some(Action::NextCommand), Some(i)) if i + 1 < history_len => Some(i + 1),
+                _ => None,
+            };
+
+            let text = app
+                .command_history_index
+                .map(|i| app.command_history[i].clone())
+                .unwrap_or_default();
+            app.command_edit = TextBox {
+                index: text.len(),
+                text,
+                ..app.command_edit
+            };
+        }
+        Some(Action::Confirm) => {
+            let line = app.command_edit.text.trim().to_string();
+            if !line.is_empty() && app.command_history.last() != Some(&line) {
+                app.command_history.push(line.clone());
+            }
+
+            app.disable_pop_up();
+            match command::parse(&line, Local::now().date_naive()) {
+                Ok(command) => return execute(app, command).await,
+                Err(e) => app.status.error(e),
+            }
+        }
+        _ => (),
+    };
+
+    AppReturn::Continue
+}
+
+pub fn paste(app: &mut App, text: &str) {
+    app.command_edit.paste(text, |_x| true);
+}
+
+pub fn initialize(app: &mut App) -> AppReturn {
+    app.command_edit = TextBox {
+        text: "".to_string(),
+        index: 0,
+        ..app.command_edit
+    };
+    app.command_history_index = None;
+    app.keybind_hints = "".to_string();
+
+    AppReturn::Continue
+}
+
+// Commands act on the selection if there is one, otherwise on the task under the cursor
+async fn execute(app: &mut App, command: Command) -> AppReturn {
+    let selected_task = match app.mode {
+        AppMode::Upcoming => app.selected_task_id(),
+        AppMode::Categories => None,
+    };
+    let targets = match selection::targets(app) {
+        ids if ids.is_empty() => selected_task.into_iter().collect(),
+        ids => ids,
+    };
+
+    match command {
+        Command::Add(text) => match app.quick_add_task(&text) {
+            Ok(new_task) => app.dispatch(IOEvent::CreateTask(new_task)).await,
+            Err(e) => app.status.error(e),
+        },
+        Command::Done => {
+            selection::update_tasks(app, &targets, "complete", |t| t.completed = true).await;
+        }
+        // Nothing moves if any of the tasks would land past the last representable date
+        Command::Move(MoveTarget::Shift(days)) => {
+            let out_of_range = app
+                .task_list
+                .tasks
+                .iter()
+                .filter(|t| targets.contains(&t.id))
+                .any(|t| add_days(t.planned_date(), days).is_none());
+            if out_of_range {
+                app.status.error(format!("shift out of range: {:+}d", days));
+            } else {
+                selection::update_tasks(app, &targets, &format!("shift {:+}d", days), |t| {
+                    if let Some(d) = add_days(t.planned_date(), days) {
+                        t.set_planned_date(d);
+                    }
+                })
+                .await;
+            }
+        }
+        Command::Move(MoveTarget::Date(d)) => {
+            selection::update_tasks(app, &targets, "reschedule", |t| t.set_planned_date(d)).await;
+        }
+        Command::RenameCategory(name) => {
+            edit_category(app, "rename", |c| c.name = name.clone()).await;
+        }
+        Command::ColorCategory(color) => {
+            edit_category(app, "recolor", |c| c.color = color.clone()).await;
+        }
+        Command::Theme(name) => match Theme::named(&name, &app.config.themes) {
+            Ok(theme) => {
+                app.theme = theme;
+                app.status.info(format!("theme {}", name));
+            }
+            Err(e) => app.status.error(e),
+        },
+        Command::Filter(filter) => {
+            app.status.info(match &filter {
+                Some(f) => format!("filtering by {}", f),
+                None => "filter cleared".to_string(),
+            });
+            app.task_list.filter = filter;
+            app.task_list_state.select(Some(0));
+        }
+        Command::Export(path) => {
+            app.dispatch(IOEvent::ExportTasks(
+                path.unwrap_or("tuitask-export.csv".to_string()),
+            ))
+            .await;
+        }
+        Command::Quit => return AppReturn::Quit,
+    }
+
+    if let Some(id) = selected_task {
+        app.reselect_task(id);
+    }
+    app.selection.clear();
+    app.selection_anchor = None;
+    app.switch_mode(app.mode.clone());
+
+    AppReturn::Continue
+}
+
+// Edits the highlighted category, or the category of the task under the cursor in Upcoming
+async fn edit_category(app: &mut App, verb: &str, edit: impl Fn(&mut Category)) {
+    let category_id = match app.mode {
+        AppMode::Categories => app
+            .category_list_state
+            .selected()
+            .and_then(|i| app.categories.get(i))
+            .map(|c| c.id),
+        AppMode::Upcoming => app
+            .selected_task_id()
+            .and_then(|id| app.task_list.tasks.iter().find(|t| t.id == id))
+            .map(|t| t.category.id),
+    };
+    let Some(category) = app
+        .categories
+        .iter_mut()
+        .find(|c| Some(c.id) == category_id)
+    else {
+        app.status.warn(format!("no category to {}", verb));
+        return;
+    };
+
+    let old_category = category.clone();
+    edit(category);
+    let new_category = category.clone();
+
+    app.task_list
+        .tasks
+        .iter_mut()
+        .filter(|t| t.category.id == new_category.id)
+        .for_each(|t| t.category = new_category.clone());
+
+    app.undo_history.record(UndoEntry {
+        description: format!("{} category {}", verb, old_category.name),
+        undo: vec![IOEvent::UpdateCategory(old_category)],
+        redo: vec![IOEvent::UpdateCategory(new_category.clone())],
+    });
+    app.dispatch(IOEvent::UpdateCategory(new_category)).await;
+}
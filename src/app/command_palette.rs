@@ -0,0 +1,78 @@
+use std::cmp::min;
+
+use crate::{
+    action::{Action, Context},
+    app::{App, AppReturn, TextBox},
+    key::Key,
+};
+
+const RECENT_ACTIONS: usize = 5;
+
+pub const CONTEXTS: &[Context] = &[Context::CommandPalette, Context::PopUp];
+
+pub async fn do_action(app: &mut App, key: Key) -> AppReturn {
+    let action = app.keymap.action_for(&key, CONTEXTS);
+    if action.is_none() {
+        let previous_search = app.palette_edit.text.clone();
+        app.palette_edit.handle_key(&key, |_x| true);
+        if app.palette_edit.text != previous_search {
+            app.palette_list_state.select(Some(0));
+        }
+    }
+
+    match action {
+        Some(Action::Cancel) => {
+            app.disable_pop_up();
+        }
+        Some(Action::ScrollUp) => {
+            app.palette_list_state.select(Some(
+                app.palette_list_state
+                    .selected()
+                    .unwrap_or(0)
+                    .saturating_sub(1),
+            ));
+        }
+        Some(Action::ScrollDown) => {
+            let num_candidates = app.palette_actions().len();
+            app.palette_list_state.select(Some(min(
+                num_candidates.saturating_sub(1),
+                app.palette_list_state.selected().unwrap_or(0) + 1,
+            )));
+        }
+        Some(Action::Confirm) => {
+            let chosen = app
+                .palette_actions()
+                .get(app.palette_list_state.selected().unwrap_or(0))
+                .map(|(action, _)| *action);
+
+            if let Some(action) = chosen {
+                app.recent_actions.retain(|a| *a != action);
+                app.recent_actions.insert(0, action);
+                app.recent_actions.truncate(RECENT_ACTIONS);
+
+                app.disable_pop_up();
+                return app.run_action(action).await;
+            }
+        }
+        _ => (),
+    };
+
+    AppReturn::Continue
+}
+
+pub fn paste(app: &mut App, text: &str) {
+    app.palette_edit.paste(text, |_x| true);
+    app.palette_list_state.select(Some(0));
+}
+
+pub fn initialize(app: &mut App) -> AppReturn {
+    app.palette_edit = TextBox {
+        text: "".to_string(),
+        index: 0,
+        ..app.palette_edit
+    };
+    app.palette_list_state.select(Some(0));
+    app.keybind_hints = app.keymap.hints(CONTEXTS);
+
+    AppReturn::Continue
+}
@@ -0,0 +1,85 @@
+use std::cmp::min;
+
+use crate::{
+    action::{Action, Context},
+    app::{App, AppReturn},
+    database::IOEvent,
+    key::Key,
+    task::{Task, TaskField},
+};
+
+pub const CONTEXTS: &[Context] = &[Context::ConflictResolver, Context::PopUp];
+
+pub async fn do_action(app: &mut App, key: Key) -> AppReturn {
+    let Some(conflict) = app.conflicts.first_mut() else {
+        app.disable_pop_up();
+        return AppReturn::Continue;
+    };
+    let selected = app.conflict_field_state.selected().unwrap_or(0);
+
+    let resolved = match app.keymap.action_for(&key, CONTEXTS) {
+        Some(Action::ScrollDown) => {
+            app.conflict_field_state
+                .select(Some(min(TaskField::ALL.len() - 1, selected + 1)));
+            None
+        }
+        Some(Action::ScrollUp) => {
+            app.conflict_field_state
+                .select(Some(selected.saturating_sub(1)));
+            None
+        }
+        Some(Action::PickMine) => {
+            conflict.take_theirs[selected] = false;
+            None
+        }
+        Some(Action::PickTheirs) => {
+            conflict.take_theirs[selected] = true;
+            None
+        }
+        Some(Action::KeepMine) => Some(Some(Task {
+            version: conflict.theirs.version,
+            ..conflict.mine.clone()
+        })),
+        Some(Action::Confirm) => Some(Some(conflict.merged())),
+        Some(Action::KeepTheirs) => Some(None),
+        // Leaves the conflict queued, to be picked up again with ResolveConflicts
+        Some(Action::Cancel) => {
+            app.conflicts_dismissed = true;
+            app.disable_pop_up();
+            app.status.warn(format!(
+                "{} edit conflict(s) left unresolved",
+                app.conflicts.len()
+            ));
+            None
+        }
+        _ => None,
+    };
+
+    // Some(None) takes their version as is, anything else is saved over it
+    if let Some(save) = resolved {
+        let conflict = app.conflicts.remove(0);
+        let selected_task = app.selected_task_id();
+
+        match save {
+            Some(t) => {
+                app.task_list.upsert(t.clone());
+                app.dispatch(IOEvent::UpdateTask(t)).await;
+            }
+            None => app.task_list.upsert(conflict.theirs),
+        }
+
+        app.disable_pop_up();
+        if let Some(selected_id) = selected_task {
+            app.reselect_task(selected_id);
+        }
+    }
+
+    AppReturn::Continue
+}
+
+pub fn initialize(app: &mut App) -> AppReturn {
+    app.conflict_field_state.select(Some(0));
+    app.keybind_hints = app.keymap.hints(CONTEXTS);
+
+    AppReturn::Continue
+}
@@ -0,0 +1,93 @@
+use std::cmp::min;
+
+use crate::{
+    action::{Action, Context},
+    app::{App, AppReturn, TextBox},
+    database::IOEvent,
+    key::Key,
+};
+
+pub const CONTEXTS: &[Context] = &[Context::DependencyEditor, Context::PopUp];
+
+pub async fn do_action(app: &mut App, key: Key) -> AppReturn {
+    let Some(editing_id) = app.selected_task().map(|t| t.id) else {
+        app.close_stale_pop_up("task");
+        return AppReturn::Continue;
+    };
+
+    let action = app.keymap.action_for(&key, CONTEXTS);
+    if action.is_none() {
+        let previous_search = app.search_edit.text.clone();
+        app.search_edit.handle_key(&key, |_x| true);
+        if app.search_edit.text != previous_search {
+            app.dependency_list_state.select(Some(0));
+        }
+    }
+
+    match action {
+        Some(Action::Cancel) => {
+            app.disable_pop_up();
+        }
+        Some(Action::ScrollUp) => {
+            app.dependency_list_state.select(Some(
+                app.dependency_list_state
+                    .selected()
+                    .unwrap_or(0)
+                    .saturating_sub(1),
+            ));
+        }
+        Some(Action::ScrollDown) => {
+            let num_candidates = app
+                .task_list
+                .fuzzy_search(&app.search_edit.text, editing_id)
+                .len();
+            app.dependency_list_state.select(Some(min(
+                num_candidates.saturating_sub(1),
+                app.dependency_list_state.selected().unwrap_or(0) + 1,
+            )));
+        }
+        Some(Action::Confirm) => {
+            let blocker_id = app
+                .task_list
+                .fuzzy_search(&app.search_edit.text, editing_id)
+                .get(app.dependency_list_state.selected().unwrap_or(0))
+                .map(|t| t.id);
+
+            if let Some(blocker_id) = blocker_id {
+                let dependency = (blocker_id, editing_id);
+
+                if app.task_list.dependencies.contains(&dependency) {
+                    app.task_list.dependencies.retain(|d| *d != dependency);
+                    app.dispatch(IOEvent::RemoveDependency(blocker_id, editing_id))
+                        .await;
+                } else if app.task_list.creates_cycle(blocker_id, editing_id) {
+                    app.status.warn("dependency would create a cycle");
+                } else {
+                    app.task_list.dependencies.push(dependency);
+                    app.dispatch(IOEvent::AddDependency(blocker_id, editing_id))
+                        .await;
+                }
+            }
+        }
+        _ => (),
+    };
+
+    AppReturn::Continue
+}
+
+pub fn paste(app: &mut App, text: &str) {
+    app.search_edit.paste(text, |_x| true);
+    app.dependency_list_state.select(Some(0));
+}
+
+pub fn initialize(app: &mut App) -> AppReturn {
+    app.search_edit = TextBox {
+        text: "".to_string(),
+        index: 0,
+        ..app.search_edit
+    };
+    app.dependency_list_state.select(Some(0));
+    app.keybind_hints = app.keymap.hints(CONTEXTS);
+
+    AppReturn::Continue
+}
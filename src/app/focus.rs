@@ -0,0 +1,70 @@
+use std::io::{stdout, Write};
+
+use crate::{
+    action::{Action, Context},
+    app::{App, AppReturn},
+    database::IOEvent,
+    key::Key,
+    task::TaskDate,
+    tracking::{Pomodoro, PomodoroPhase},
+};
+use chrono::Utc;
+
+pub const CONTEXTS: &[Context] = &[Context::Focus, Context::PopUp];
+
+pub async fn do_action(app: &mut App, key: Key) -> AppReturn {
+    match app.keymap.action_for(&key, CONTEXTS) {
+        Some(Action::Cancel) => {
+            app.pomodoro = None;
+            app.disable_pop_up();
+        }
+        Some(Action::TogglePause) => {
+            if let Some(pomodoro) = app.pomodoro.as_mut() {
+                pomodoro.toggle_pause();
+            }
+        }
+        Some(Action::SkipPhase) => {
+            finish_phase(app, false).await;
+        }
+        _ => (),
+    };
+
+    AppReturn::Continue
+}
+
+// Called on every tick while focusing, ends the current interval once its time is up
+pub async fn update_on_tick(app: &mut App) {
+    if app.pomodoro.as_ref().is_some_and(|p| p.is_finished()) {
+        finish_phase(app, true).await;
+    }
+}
+
+// Work intervals only count towards stats if they ran to completion rather than being skipped
+async fn finish_phase(app: &mut App, completed: bool) {
+    let Some(pomodoro) = app.pomodoro.as_mut() else {
+        return;
+    };
+
+    let log_event = (completed && pomodoro.phase == PomodoroPhase::Work)
+        .then(|| IOEvent::LogPomodoro(pomodoro.task_id, pomodoro.phase_started_at, Utc::now()));
+
+    if completed {
+        print!("\x07");
+        let _ = stdout().flush();
+    }
+    pomodoro.advance(&app.config);
+
+    if let Some(io_event) = log_event {
+        app.dispatch(io_event).await;
+    }
+}
+
+pub fn initialize(app: &mut App) -> AppReturn {
+    // Action::Focus only opens this on a task
+    if let TaskDate::Task(task) = &app.task_list.current_taskdate {
+        app.pomodoro = Some(Pomodoro::new(task.id, task.name.clone(), &app.config));
+    }
+    app.keybind_hints = app.keymap.hints(CONTEXTS);
+
+    AppReturn::Continue
+}
@@ -0,0 +1,37 @@
+use crate::{
+    action::{Action, Context},
+    app::{App, AppReturn},
+    key::Key,
+};
+
+pub const CONTEXTS: &[Context] = &[Context::Help];
+
+pub async fn do_action(app: &mut App, key: Key) -> AppReturn {
+    match app.keymap.action_for(&key, CONTEXTS) {
+        Some(Action::Cancel) => close(app),
+        // Kept from scrolling past the end while drawing, which knows how tall the list is
+        Some(Action::ScrollDown) => app.help_scroll += 1,
+        Some(Action::ScrollUp) => app.help_scroll = app.help_scroll.saturating_sub(1),
+        _ => (),
+    };
+
+    AppReturn::Continue
+}
+
+// Opens over the view or pop up the keys are listed for, which is left as it is underneath
+pub fn initialize(app: &mut App) -> AppReturn {
+    app.help = true;
+    app.help_scroll = 0;
+    app.keybind_hints = app.keymap.hints(CONTEXTS);
+
+    AppReturn::Continue
+}
+
+fn close(app: &mut App) {
+    app.help = false;
+    if app.pop_up.is_some() {
+        app.keybind_hints = app.keymap.hints(app.pop_up_contexts());
+    } else {
+        app.switch_mode(app.mode.clone());
+    }
+}
@@ -0,0 +1,43 @@
+use std::cmp::min;
+
+use crate::{
+    action::{Action, Context},
+    app::{App, AppReturn},
+    key::Key,
+};
+
+pub const CONTEXTS: &[Context] = &[Context::History, Context::PopUp];
+
+pub async fn do_action(app: &mut App, key: Key) -> AppReturn {
+    match app.keymap.action_for(&key, CONTEXTS) {
+        Some(Action::Cancel) => {
+            app.disable_pop_up();
+        }
+        Some(Action::ScrollDown) => {
+            app.history_list_state.select(Some(min(
+                app.history.len().saturating_sub(1),
+                app.history_list_state.selected().unwrap_or(0) + 1,
+            )));
+        }
+        Some(Action::ScrollUp) => {
+            app.history_list_state.select(Some(
+                app.history_list_state
+                    .selected()
+                    .unwrap_or(0)
+                    .saturating_sub(1),
+            ));
+        }
+        _ => (),
+    };
+
+    AppReturn::Continue
+}
+
+// Clears the previous task's history, the selected one is loaded in by the IOHandler
+pub fn initialize(app: &mut App) -> AppReturn {
+    app.history.clear();
+    app.history_list_state.select(Some(0));
+    app.keybind_hints = app.keymap.hints(CONTEXTS);
+
+    AppReturn::Continue
+}
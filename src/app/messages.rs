@@ -0,0 +1,44 @@
+use std::cmp::min;
+
+use crate::{
+    action::{Action, Context},
+    app::{App, AppReturn},
+    key::Key,
+};
+
+pub const CONTEXTS: &[Context] = &[Context::Messages, Context::PopUp];
+
+pub async fn do_action(app: &mut App, key: Key) -> AppReturn {
+    match app.keymap.action_for(&key, CONTEXTS) {
+        Some(Action::Cancel) => {
+            app.disable_pop_up();
+        }
+        Some(Action::ScrollDown) => {
+            app.messages_list_state.select(Some(min(
+                app.status.messages().len().saturating_sub(1),
+                app.messages_list_state.selected().unwrap_or(0) + 1,
+            )));
+        }
+        Some(Action::ScrollUp) => {
+            app.messages_list_state.select(Some(
+                app.messages_list_state
+                    .selected()
+                    .unwrap_or(0)
+                    .saturating_sub(1),
+            ));
+        }
+        _ => (),
+    };
+
+    AppReturn::Continue
+}
+
+// Starts on the newest message, and the toasts go away since they're all listed here
+pub fn initialize(app: &mut App) -> AppReturn {
+    app.messages_list_state
+        .select(Some(app.status.messages().len().saturating_sub(1)));
+    app.status.dismiss_toasts();
+    app.keybind_hints = app.keymap.hints(CONTEXTS);
+
+    AppReturn::Continue
+}
@@ -0,0 +1,107 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    action::Action,
+    app::{App, AppMode, AppPopUp, AppReturn, SelectedField},
+    key::Key,
+    mouse::Mouse,
+};
+use ratatui::prelude::Rect;
+
+use super::{categories, selection};
+
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+// What's drawn at a clickable area of the screen, recorded by the UI as it draws
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ClickTarget {
+    Tab(Action),
+    UpcomingRow(usize),
+    Category(usize),
+    CategoryTask(i32),
+    Field(SelectedField),
+    CategoryOption(usize), // in the task editor's category list
+}
+
+pub async fn do_action(app: &mut App, mouse: Mouse) -> AppReturn {
+    // Events can still arrive just after capture is turned off
+    if !app.mouse_capture {
+        return AppReturn::Continue;
+    }
+
+    match mouse {
+        // Only the wheel does anything over the help overlay, scrolling it
+        Mouse::Click(..) if app.help => AppReturn::Continue,
+        Mouse::Click(column, row, ctrl) => {
+            // Pop ups are drawn over the view, so their targets are recorded last
+            let Some(target) = app
+                .click_targets
+                .iter()
+                .rev()
+                .find(|(area, _)| contains(area, column, row))
+                .map(|(_, target)| *target)
+            else {
+                return AppReturn::Continue;
+            };
+
+            let double_click = app
+                .last_click
+                .is_some_and(|(at, last)| last == target && at.elapsed() < DOUBLE_CLICK);
+            app.last_click = Some((Instant::now(), target));
+
+            click(app, target, ctrl, double_click).await
+        }
+        // Scrolls like the arrow keys would
+        Mouse::ScrollUp if app.pop_up.is_some() || app.help => app.do_action(Key::Up).await,
+        Mouse::ScrollDown if app.pop_up.is_some() || app.help => app.do_action(Key::Down).await,
+        Mouse::ScrollUp => app.run_action(Action::ScrollUp).await,
+        Mouse::ScrollDown => app.run_action(Action::ScrollDown).await,
+        Mouse::Unused => AppReturn::Continue,
+    }
+}
+
+async fn click(app: &mut App, target: ClickTarget, ctrl: bool, double_click: bool) -> AppReturn {
+    match (&app.pop_up, target) {
+        (None, ClickTarget::Tab(action)) => return app.run_action(action).await,
+        (None, ClickTarget::UpcomingRow(index)) => {
+            // The task under the cursor is normally found while drawing, but actions on it
+            // shouldn't have to wait for that
+            app.task_list_state.select(Some(index));
+            app.task_list.get_upcoming_list(index, 1);
+
+            if ctrl {
+                return selection::do_action(app, Action::ToggleSelect).await;
+            } else if double_click {
+                return app.run_action(Action::EditTask).await;
+            }
+        }
+        (None, ClickTarget::Category(index)) => {
+            app.category_list_state.select(Some(index));
+            if double_click {
+                return app.run_action(Action::EditCategory).await;
+            }
+        }
+        // Tasks listed under a category don't have a cursor, so clicking one selects it
+        (None, ClickTarget::CategoryTask(id)) => {
+            if !app.selection.remove(&id) {
+                app.selection.insert(id);
+            }
+            if app.mode == AppMode::Categories {
+                categories::initialize(app);
+            }
+        }
+        (Some(AppPopUp::TaskEditor | AppPopUp::Rescheduler), ClickTarget::Field(field)) => {
+            app.task_edit_field = field;
+        }
+        (Some(AppPopUp::TaskEditor), ClickTarget::CategoryOption(index)) => {
+            app.category_edit_state.select(Some(index));
+        }
+        _ => (),
+    }
+
+    AppReturn::Continue
+}
+
+fn contains(area: &Rect, column: u16, row: u16) -> bool {
+    (area.left()..area.right()).contains(&column) && (area.top()..area.bottom()).contains(&row)
+}
@@ -0,0 +1,46 @@
+use crate::{
+    action::{Action, Context},
+    app::{App, AppReturn, TextBox},
+    database::IOEvent,
+    key::Key,
+};
+
+pub const CONTEXTS: &[Context] = &[Context::QuickAdd, Context::PopUp];
+
+pub async fn do_action(app: &mut App, key: Key) -> AppReturn {
+    let action = app.keymap.action_for(&key, CONTEXTS);
+    if action.is_none() {
+        app.quick_add_edit.handle_key(&key, |_x| true);
+    }
+
+    match action {
+        Some(Action::Cancel) => {
+            app.disable_pop_up();
+        }
+        Some(Action::Confirm) => match app.quick_add_task(&app.quick_add_edit.text) {
+            Ok(new_task) => {
+                app.dispatch(IOEvent::CreateTask(new_task)).await;
+                app.disable_pop_up();
+            }
+            Err(e) => app.status.error(e),
+        },
+        _ => (),
+    };
+
+    AppReturn::Continue
+}
+
+pub fn paste(app: &mut App, text: &str) {
+    app.quick_add_edit.paste(text, |_x| true);
+}
+
+pub fn initialize(app: &mut App) -> AppReturn {
+    app.quick_add_edit = TextBox {
+        text: "".to_string(),
+        index: 0,
+        ..app.quick_add_edit
+    };
+    app.keybind_hints = app.keymap.hints(CONTEXTS);
+
+    AppReturn::Continue
+}
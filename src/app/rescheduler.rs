@@ -0,0 +1,96 @@
+use crate::{
+    action::{Action, Context},
+    app::{parse_date, App, AppReturn, SelectedField, TextBox},
+    key::Key,
+};
+use chrono::{Datelike, Local};
+
+use super::selection;
+
+pub const CONTEXTS: &[Context] = &[Context::Rescheduler, Context::PopUp];
+
+pub async fn do_action(app: &mut App, key: Key) -> AppReturn {
+    let action = app.keymap.action_for(&key, CONTEXTS);
+    if action.is_none() {
+        focused_field(app).handle_key(&key, |x| x.parse::<isize>().is_ok());
+    }
+
+    match action {
+        Some(Action::Cancel) => {
+            app.disable_pop_up();
+        }
+        Some(Action::Confirm) => {
+            if let Some(new_date) = parse_date(&app.year_edit, &app.month_edit, &app.date_edit) {
+                let ids = app.bulk_ids.clone();
+                let selected_task = app.selected_task_id();
+                selection::update_tasks(app, &ids, "reschedule", |t| t.set_planned_date(new_date))
+                    .await;
+
+                app.disable_pop_up();
+                match selected_task {
+                    Some(id) => app.reselect_task(id),
+                    None => app.task_list_state.select(Some(0)),
+                }
+            }
+        }
+        Some(Action::NextField) => {
+            app.task_edit_field = match app.task_edit_field {
+                SelectedField::Year => SelectedField::Month,
+                SelectedField::Month => SelectedField::Date,
+                _ => SelectedField::Year,
+            };
+        }
+        Some(Action::PreviousField) => {
+            app.task_edit_field = match app.task_edit_field {
+                SelectedField::Year => SelectedField::Date,
+                SelectedField::Date => SelectedField::Month,
+                _ => SelectedField::Year,
+            };
+        }
+        _ => (),
+    };
+
+    AppReturn::Continue
+}
+
+pub fn paste(app: &mut App, text: &str) {
+    focused_field(app).paste(text, |x| x.parse::<isize>().is_ok());
+}
+
+fn focused_field(app: &mut App) -> &mut TextBox {
+    match app.task_edit_field {
+        SelectedField::Year => &mut app.year_edit,
+        SelectedField::Month => &mut app.month_edit,
+        _ => &mut app.date_edit,
+    }
+}
+
+// Moves the tasks in bulk_ids. Defaults to today so that [Enter] immediately moves them to today
+pub fn initialize(app: &mut App) -> AppReturn {
+    let today = Local::now().date_naive();
+    let (year, month, date) = (
+        today.year().to_string(),
+        today.month().to_string(),
+        today.day().to_string(),
+    );
+
+    app.year_edit = TextBox {
+        index: year.len(),
+        text: year,
+        ..app.year_edit
+    };
+    app.month_edit = TextBox {
+        index: month.len(),
+        text: month,
+        ..app.month_edit
+    };
+    app.date_edit = TextBox {
+        index: date.len(),
+        text: date,
+        ..app.date_edit
+    };
+    app.task_edit_field = SelectedField::Date;
+    app.keybind_hints = app.keymap.hints(CONTEXTS);
+
+    AppReturn::Continue
+}
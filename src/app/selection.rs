@@ -0,0 +1,225 @@
+use std::collections::HashSet;
+
+use crate::{
+    action::Action,
+    app::{App, AppMode, AppPopUp, AppReturn},
+    clipboard,
+    database::IOEvent,
+    task::{Task, TaskDate},
+    undo::UndoEntry,
+};
+use chrono::Local;
+
+// Selection actions in Upcoming and Categories, plus the bulk actions while anything is selected
+pub async fn do_action(app: &mut App, action: Action) -> AppReturn {
+    match (&action, &app.mode) {
+        (Action::ToggleSelect, _) => {
+            let ids = under_cursor(app);
+            toggle(&mut app.selection, &ids);
+        }
+        // Starts a range at the cursor, pressing again keeps everything between it and the cursor
+        (Action::SelectRange, AppMode::Upcoming) => match app.selection_anchor {
+            Some(_) => {
+                app.selection = app.selected_ids();
+                app.selection_anchor = None;
+            }
+            None => app.selection_anchor = app.selected_task_id(),
+        },
+        (Action::SelectGroup, AppMode::Upcoming) => {
+            let ids = match &app.task_list.current_taskdate {
+                TaskDate::Task(t) if t.planned_date() < Local::now().date_naive() => {
+                    app.task_list.overdue_ids()
+                }
+                TaskDate::Task(t) => app.task_list.ids_planned_on(t.planned_date()),
+                TaskDate::Date(d) => app.task_list.ids_planned_on(*d),
+                TaskDate::Overdue(_) => app.task_list.overdue_ids(),
+            };
+            toggle(&mut app.selection, &ids);
+        }
+        (Action::ClearSelection, _) => (),
+        (Action::CompleteSelected, _) => {
+            let ids = targets(app);
+            update_tasks(app, &ids, "complete", |t| t.completed = true).await;
+        }
+        (Action::DeleteSelected, _) => {
+            let ids = targets(app);
+            delete_tasks(app, &ids).await;
+        }
+        (Action::MoveSelected, _) => {
+            app.bulk_ids = targets(app);
+            app.enable_pop_up(AppPopUp::Rescheduler);
+        }
+        (Action::ShiftSelected, _) => {
+            app.bulk_ids = targets(app);
+            app.enable_pop_up(AppPopUp::Shifter);
+        }
+        (Action::RecategorizeSelected, _) => {
+            app.bulk_ids = targets(app);
+            app.enable_pop_up(AppPopUp::CategoryPicker);
+        }
+        // Copies what's under the cursor when nothing is selected
+        (Action::YankText | Action::YankMarkdown, _) => {
+            let ids = match targets(app) {
+                ids if ids.is_empty() => under_cursor(app),
+                ids => ids,
+            };
+            let tasks: Vec<Task> = app
+                .task_list
+                .tasks
+                .iter()
+                .filter(|t| ids.contains(&t.id))
+                .cloned()
+                .collect();
+
+            if !tasks.is_empty() {
+                let text = match action {
+                    Action::YankMarkdown => clipboard::as_markdown(&tasks),
+                    _ => clipboard::as_text(&tasks),
+                };
+                match clipboard::copy(&text) {
+                    Ok(()) => app.status.info(describe("copied", &tasks)),
+                    Err(e) => app.status.error(format!("couldn't copy: {}", e)),
+                };
+            }
+        }
+        _ => return AppReturn::Continue,
+    }
+
+    // Anything other than building up the selection uses it up
+    if !matches!(
+        action,
+        Action::ToggleSelect | Action::SelectRange | Action::SelectGroup
+    ) {
+        app.selection.clear();
+        app.selection_anchor = None;
+    }
+
+    // Hints show the bulk actions only while something is selected
+    if app.pop_up.is_none() {
+        match app.mode {
+            AppMode::Upcoming => super::upcoming::initialize(app),
+            AppMode::Categories => super::categories::initialize(app),
+        }
+    }
+
+    AppReturn::Continue
+}
+
+// The task under the cursor, or every task in the highlighted category
+fn under_cursor(app: &App) -> Vec<i32> {
+    match app.mode {
+        AppMode::Upcoming => app.selected_task_id().into_iter().collect(),
+        AppMode::Categories => {
+            let Some(category) = app
+                .categories
+                .get(app.category_list_state.selected().unwrap_or(0))
+            else {
+                return Vec::new();
+            };
+            let category_id = category.id;
+            app.task_list
+                .tasks
+                .iter()
+                .filter(|t| t.category.id == category_id && app.task_list.is_visible(t))
+                .map(|t| t.id)
+                .collect()
+        }
+    }
+}
+
+// Selects all the ids unless they are all selected already, in which case they're deselected
+fn toggle(selection: &mut HashSet<i32>, ids: &[i32]) {
+    if ids.iter().all(|id| selection.contains(id)) {
+        ids.iter().for_each(|id| {
+            selection.remove(id);
+        });
+    } else {
+        selection.extend(ids);
+    }
+}
+
+// Selected tasks that are still loaded, in list order
+pub(super) fn targets(app: &App) -> Vec<i32> {
+    let selected = app.selected_ids();
+    app.task_list
+        .tasks
+        .iter()
+        .filter(|t| selected.contains(&t.id))
+        .map(|t| t.id)
+        .collect()
+}
+
+// Edits the tasks locally and saves them in one transaction, as a single undoable action
+pub(super) async fn update_tasks(
+    app: &mut App,
+    ids: &[i32],
+    action: &str,
+    edit: impl Fn(&mut Task),
+) {
+    let mut old_tasks = Vec::new();
+    let mut new_tasks = Vec::new();
+    for t in app
+        .task_list
+        .tasks
+        .iter_mut()
+        .filter(|t| ids.contains(&t.id))
+    {
+        old_tasks.push(t.clone());
+        edit(t);
+        new_tasks.push(t.clone());
+    }
+
+    if new_tasks.is_empty() {
+        return;
+    }
+
+    app.undo_history.record(UndoEntry {
+        description: describe(action, &new_tasks),
+        undo: vec![IOEvent::UpdateTasks(old_tasks)],
+        redo: vec![IOEvent::UpdateTasks(new_tasks.clone())],
+    });
+    app.dispatch(IOEvent::UpdateTasks(new_tasks)).await;
+}
+
+pub(super) async fn delete_tasks(app: &mut App, ids: &[i32]) {
+    let deleted_tasks: Vec<Task> = app
+        .task_list
+        .tasks
+        .iter()
+        .filter(|t| ids.contains(&t.id))
+        .cloned()
+        .collect();
+
+    if deleted_tasks.is_empty() {
+        return;
+    }
+
+    // Dependencies are cascaded away with the tasks, so they're restored alongside them
+    let mut undo = vec![IOEvent::RestoreTasks(deleted_tasks.clone())];
+    undo.extend(
+        app.task_list
+            .dependencies
+            .iter()
+            .filter(|(blocker, blocked)| ids.contains(blocker) || ids.contains(blocked))
+            .map(|(blocker, blocked)| IOEvent::AddDependency(*blocker, *blocked)),
+    );
+
+    ids.iter().for_each(|id| app.task_list.remove(*id));
+    if app.timer.as_ref().is_some_and(|t| ids.contains(&t.task_id)) {
+        app.timer = None;
+    }
+
+    app.undo_history.record(UndoEntry {
+        description: describe("delete", &deleted_tasks),
+        undo,
+        redo: vec![IOEvent::DeleteTasks(deleted_tasks.clone())],
+    });
+    app.dispatch(IOEvent::DeleteTasks(deleted_tasks)).await;
+}
+
+fn describe(action: &str, tasks: &[Task]) -> String {
+    match tasks {
+        [t] => format!("{} {}", action, t.name),
+        _ => format!("{} {} tasks", action, tasks.len()),
+    }
+}
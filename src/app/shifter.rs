@@ -0,0 +1,75 @@
+use crate::{
+    action::{Action, Context},
+    app::{App, AppReturn, TextBox},
+    key::Key,
+    task::add_days,
+};
+
+use super::selection;
+
+pub const CONTEXTS: &[Context] = &[Context::Shifter, Context::PopUp];
+
+pub async fn do_action(app: &mut App, key: Key) -> AppReturn {
+    let action = app.keymap.action_for(&key, CONTEXTS);
+    if action.is_none() {
+        app.shift_edit.handle_key(&key, is_shift);
+    }
+
+    match action {
+        Some(Action::Cancel) => {
+            app.disable_pop_up();
+        }
+        Some(Action::Confirm) => {
+            if let Ok(days) = app.shift_edit.text.parse::<i64>() {
+                let ids = app.bulk_ids.clone();
+                // Nothing moves if any of the tasks would land past the last representable date
+                let out_of_range = app
+                    .task_list
+                    .tasks
+                    .iter()
+                    .filter(|t| ids.contains(&t.id))
+                    .any(|t| add_days(t.planned_date(), days).is_none());
+                if out_of_range {
+                    app.status.error(format!("shift out of range: {:+}d", days));
+                    return AppReturn::Continue;
+                }
+
+                let selected_task = app.selected_task_id();
+                selection::update_tasks(app, &ids, &format!("shift {:+}d", days), |t| {
+                    if let Some(d) = add_days(t.planned_date(), days) {
+                        t.set_planned_date(d);
+                    }
+                })
+                .await;
+
+                app.disable_pop_up();
+                if let Some(id) = selected_task {
+                    app.reselect_task(id);
+                }
+            }
+        }
+        _ => (),
+    };
+
+    AppReturn::Continue
+}
+
+pub fn paste(app: &mut App, text: &str) {
+    app.shift_edit.paste(text, is_shift);
+}
+
+// A whole number of days, which may still be just its sign while being typed
+fn is_shift(x: &str) -> bool {
+    x == "-" || x == "+" || x.parse::<i64>().is_ok()
+}
+
+pub fn initialize(app: &mut App) -> AppReturn {
+    app.shift_edit = TextBox {
+        text: "+1".to_string(),
+        index: 2,
+        ..app.shift_edit
+    };
+    app.keybind_hints = app.keymap.hints(CONTEXTS);
+
+    AppReturn::Continue
+}
@@ -0,0 +1,41 @@
+use std::cmp::min;
+
+use crate::{
+    action::{Action, Context},
+    app::{App, AppReturn},
+    key::Key,
+};
+
+pub const CONTEXTS: &[Context] = &[Context::Stats, Context::PopUp];
+
+pub async fn do_action(app: &mut App, key: Key) -> AppReturn {
+    match app.keymap.action_for(&key, CONTEXTS) {
+        Some(Action::Cancel) => {
+            app.disable_pop_up();
+        }
+        Some(Action::ScrollDown) => {
+            app.stats_list_state.select(Some(min(
+                app.stats.by_task.len().saturating_sub(1),
+                app.stats_list_state.selected().unwrap_or(0) + 1,
+            )));
+        }
+        Some(Action::ScrollUp) => {
+            app.stats_list_state.select(Some(
+                app.stats_list_state
+                    .selected()
+                    .unwrap_or(0)
+                    .saturating_sub(1),
+            ));
+        }
+        _ => (),
+    };
+
+    AppReturn::Continue
+}
+
+pub fn initialize(app: &mut App) -> AppReturn {
+    app.stats_list_state.select(Some(0));
+    app.keybind_hints = app.keymap.hints(CONTEXTS);
+
+    AppReturn::Continue
+}
@@ -1,148 +1,161 @@
 use std::cmp::min;
 
 use crate::{
-    app::{App, AppReturn, SelectedField, TextBox},
+    action::{Action, Context},
+    app::{parse_date, parse_optional_date, App, AppReturn, SelectedField, TextBox, Verify},
     database::IOEvent,
     key::Key,
-    task::{Task, TaskDate},
+    task::{format_minutes, parse_minutes, Task, TaskDate},
+    undo::UndoEntry,
 };
-use chrono::{Datelike, NaiveDate};
+use chrono::{Datelike, Local};
 use ratatui::widgets::ListState;
 
+pub const CONTEXTS: &[Context] = &[Context::TaskEditor];
+
 pub async fn do_action(app: &mut App, key: Key) -> AppReturn {
+    let action = app.keymap.action_for(&key, CONTEXTS);
+
+    // Keys bound to the editor itself aren't typed into the focused field
     match app.task_edit_field {
-        SelectedField::Name => handle_textbox(&mut app.name_edit, &key, |_x| true),
-        SelectedField::Year => {
-            handle_textbox(&mut app.year_edit, &key, |x| x.parse::<isize>().is_ok())
-        }
-        SelectedField::Month => {
-            handle_textbox(&mut app.month_edit, &key, |x| x.parse::<isize>().is_ok())
-        }
-        SelectedField::Date => {
-            handle_textbox(&mut app.date_edit, &key, |x| x.parse::<isize>().is_ok())
-        }
         SelectedField::Category => {
-            handle_selector(app.categories.len(), &mut app.category_edit_state, &key)
+            handle_selector(app.categories.len(), &mut app.category_edit_state, action)
+        }
+        _ if matches!(
+            action,
+            Some(Action::NextField | Action::PreviousField | Action::Save | Action::Cancel)
+        ) => {}
+        _ => {
+            if let Some((text_box, verify)) = focused_field(app) {
+                text_box.handle_key(&key, verify);
+            }
         }
     };
 
-    match key {
-        Key::Esc | Key::Ctrl('c') => {
+    match action {
+        Some(Action::Cancel) => {
             app.disable_pop_up();
         }
-        Key::Enter => {
+        Some(Action::Save) => {
             // TODO: needs to automatically select the new/edited task in the list
-            if !app.name_edit.text.is_empty()
-                && app.year_edit.text.parse::<i32>().is_ok()
-                && app.month_edit.text.parse::<u32>().is_ok()
-                && app.date_edit.text.parse::<u32>().is_ok()
-                && NaiveDate::from_ymd_opt(
-                    app.year_edit.text.parse::<i32>().unwrap(),
-                    app.month_edit.text.parse::<u32>().unwrap(),
-                    app.date_edit.text.parse::<u32>().unwrap(),
+            let valid = !app.name_edit.text.is_empty()
+                && parse_date(&app.year_edit, &app.month_edit, &app.date_edit).is_some()
+                && parse_optional_date(
+                    &app.scheduled_year_edit,
+                    &app.scheduled_month_edit,
+                    &app.scheduled_date_edit,
                 )
                 .is_some()
-            {
-                if app.editing_task {
-                    let editing_task = &mut app.task_list.tasks[app.task_list.selected_index];
-                    *editing_task = Task {
-                        due_date: NaiveDate::from_ymd_opt(
-                            app.year_edit.text.parse::<i32>().unwrap(),
-                            app.month_edit.text.parse::<u32>().unwrap(),
-                            app.date_edit.text.parse::<u32>().unwrap(),
-                        )
-                        .unwrap(),
-                        name: app.name_edit.text.clone(),
-                        category: app.categories[app.category_edit_state.selected().unwrap()]
-                            .clone(),
-                        ..editing_task.clone()
-                    };
-
-                    let io_event = IOEvent::UpdateTask(editing_task.clone());
-                    app.dispatch(io_event).await;
-                } else {
-                    let new_task = Task {
-                        due_date: NaiveDate::from_ymd_opt(
-                            app.year_edit.text.parse::<i32>().unwrap(),
-                            app.month_edit.text.parse::<u32>().unwrap(),
-                            app.date_edit.text.parse::<u32>().unwrap(),
-                        )
-                        .unwrap(),
-                        name: app.name_edit.text.clone(),
-                        completed: false,
-                        category: app.categories[app.category_edit_state.selected().unwrap()]
-                            .clone(),
-                        id: -1,
-                    };
-                    app.dispatch(IOEvent::CreateTask(new_task)).await;
-                }
-
-                app.disable_pop_up();
+                && (app.estimate_edit.text.is_empty()
+                    || parse_minutes(&app.estimate_edit.text).is_some());
+            if !valid {
+                return AppReturn::Continue;
+            }
+
+            let estimate_minutes = parse_minutes(&app.estimate_edit.text);
+            let scheduled_date = parse_optional_date(
+                &app.scheduled_year_edit,
+                &app.scheduled_month_edit,
+                &app.scheduled_date_edit,
+            )
+            .unwrap();
+
+            let Some(category) = app
+                .categories
+                .get(app.category_edit_state.selected().unwrap_or(0))
+                .cloned()
+            else {
+                app.close_stale_pop_up("category");
+                return AppReturn::Continue;
+            };
+
+            if app.editing_task {
+                let selected_task = app.selected_task_id();
+                let Some(editing_task) = app
+                    .task_list
+                    .tasks
+                    .iter_mut()
+                    .find(|t| Some(t.id) == selected_task)
+                else {
+                    app.close_stale_pop_up("task");
+                    return AppReturn::Continue;
+                };
+                let old_task = editing_task.clone();
+                *editing_task = Task {
+                    due_date: parse_date(&app.year_edit, &app.month_edit, &app.date_edit).unwrap(),
+                    scheduled_date,
+                    estimate_minutes,
+                    name: app.name_edit.text.clone(),
+                    category: category.clone(),
+                    ..editing_task.clone()
+                };
+
+                let new_task = editing_task.clone();
+                app.undo_history.record(UndoEntry {
+                    description: format!("edit {}", old_task.name),
+                    undo: vec![IOEvent::UpdateTask(old_task)],
+                    redo: vec![IOEvent::UpdateTask(new_task.clone())],
+                });
+                app.dispatch(IOEvent::UpdateTask(new_task)).await;
+            } else {
+                let new_task = Task {
+                    due_date: parse_date(&app.year_edit, &app.month_edit, &app.date_edit).unwrap(),
+                    name: app.name_edit.text.clone(),
+                    completed: false,
+                    category,
+                    id: -1,
+                    version: 1,
+                    scheduled_date,
+                    estimate_minutes,
+                    priority: 0,
+                    tags: Vec::new(),
+                };
+                app.dispatch(IOEvent::CreateTask(new_task)).await;
             }
+
+            app.disable_pop_up();
         }
-        Key::Tab => {
+        Some(Action::NextField) => {
             app.task_edit_field = match app.task_edit_field {
                 SelectedField::Name => SelectedField::Year,
                 SelectedField::Year => SelectedField::Month,
                 SelectedField::Month => SelectedField::Date,
-                SelectedField::Date => SelectedField::Category,
+                SelectedField::Date => SelectedField::ScheduledYear,
+                SelectedField::ScheduledYear => SelectedField::ScheduledMonth,
+                SelectedField::ScheduledMonth => SelectedField::ScheduledDate,
+                SelectedField::ScheduledDate => SelectedField::Estimate,
+                SelectedField::Estimate => SelectedField::Category,
                 SelectedField::Category => SelectedField::Name,
             };
         }
-        Key::ShiftTab => {
+        Some(Action::PreviousField) => {
             app.task_edit_field = match app.task_edit_field {
                 SelectedField::Name => SelectedField::Category,
                 SelectedField::Year => SelectedField::Name,
                 SelectedField::Month => SelectedField::Year,
                 SelectedField::Date => SelectedField::Month,
-                SelectedField::Category => SelectedField::Date,
+                SelectedField::ScheduledYear => SelectedField::Date,
+                SelectedField::ScheduledMonth => SelectedField::ScheduledYear,
+                SelectedField::ScheduledDate => SelectedField::ScheduledMonth,
+                SelectedField::Estimate => SelectedField::ScheduledDate,
+                SelectedField::Category => SelectedField::Estimate,
             };
         }
         _ => (),
     };
 
-    fn handle_textbox(current_field: &mut TextBox, key: &Key, verify: fn(&str) -> bool) {
-        match key {
-            Key::Number(c) | Key::Char(c) => {
-                let mut proposed_text = current_field.text.clone();
-                proposed_text.insert(current_field.index, *c);
-
-                if proposed_text.len() <= current_field.max_length && verify(&proposed_text) {
-                    *current_field = TextBox {
-                        text: proposed_text,
-                        index: current_field.index + 1,
-                        ..*current_field
-                    };
-                }
-            }
-            Key::Left => {
-                if current_field.index > 0 {
-                    current_field.index -= 1;
-                }
-            }
-            Key::Right => {
-                if current_field.index < current_field.text.len() {
-                    current_field.index += 1;
-                }
-            }
-            Key::Backspace => {
-                if current_field.index > 0 {
-                    current_field.text.remove(current_field.index - 1);
-                    current_field.index -= 1;
-                }
-            }
-            _ => {}
-        };
-    }
-
-    fn handle_selector(num_categories: usize, category_edit_state: &mut ListState, key: &Key) {
-        match key {
-            Key::Char('k') | Key::Up => {
+    fn handle_selector(
+        num_categories: usize,
+        category_edit_state: &mut ListState,
+        action: Option<Action>,
+    ) {
+        match action {
+            Some(Action::PreviousCategory) => {
                 category_edit_state.select(Some(
                     category_edit_state.selected().unwrap().saturating_sub(1),
                 ));
             }
-            Key::Char('j') | Key::Down => {
+            Some(Action::NextCategory) => {
                 category_edit_state.select(Some(min(
                     num_categories.saturating_sub(1),
                     category_edit_state.selected().unwrap() + 1,
@@ -155,6 +168,34 @@ pub async fn do_action(app: &mut App, key: Key) -> AppReturn {
     AppReturn::Continue
 }
 
+pub fn paste(app: &mut App, text: &str) {
+    if let Some((text_box, verify)) = focused_field(app) {
+        text_box.paste(text, verify);
+    }
+}
+
+// Text box of the selected field with the check its text has to pass, None for the category list
+fn focused_field(app: &mut App) -> Option<(&mut TextBox, Verify)> {
+    fn is_number(x: &str) -> bool {
+        x.parse::<isize>().is_ok()
+    }
+
+    Some(match app.task_edit_field {
+        SelectedField::Name => (&mut app.name_edit, |_x| true),
+        SelectedField::Year => (&mut app.year_edit, is_number),
+        SelectedField::Month => (&mut app.month_edit, is_number),
+        SelectedField::Date => (&mut app.date_edit, is_number),
+        SelectedField::ScheduledYear => (&mut app.scheduled_year_edit, is_number),
+        SelectedField::ScheduledMonth => (&mut app.scheduled_month_edit, is_number),
+        SelectedField::ScheduledDate => (&mut app.scheduled_date_edit, is_number),
+        SelectedField::Estimate => (&mut app.estimate_edit, |x| {
+            x.chars()
+                .all(|c| c.is_ascii_digit() || c == 'h' || c == 'm')
+        }),
+        SelectedField::Category => return None,
+    })
+}
+
 pub fn initialize(app: &mut App) -> AppReturn {
     let (name, year, month, date, category_index): (String, String, String, String, usize) =
         match &app.task_list.current_taskdate {
@@ -179,6 +220,16 @@ pub fn initialize(app: &mut App) -> AppReturn {
                 d.day().to_string(),
                 0,
             ),
+            TaskDate::Overdue(_) => {
+                let today = Local::now().date_naive();
+                (
+                    "".to_string(),
+                    today.year().to_string(),
+                    today.month().to_string(),
+                    today.day().to_string(),
+                    0,
+                )
+            }
         };
 
     app.name_edit = TextBox {
@@ -201,9 +252,51 @@ pub fn initialize(app: &mut App) -> AppReturn {
         text: date,
         ..app.date_edit
     };
+
+    let (scheduled_year, scheduled_month, scheduled_date) = match &app.task_list.current_taskdate {
+        TaskDate::Task(Task {
+            scheduled_date: Some(d),
+            ..
+        }) if app.editing_task => (
+            d.year().to_string(),
+            d.month().to_string(),
+            d.day().to_string(),
+        ),
+        _ => ("".to_string(), "".to_string(), "".to_string()),
+    };
+
+    app.scheduled_year_edit = TextBox {
+        index: scheduled_year.len(),
+        text: scheduled_year,
+        ..app.scheduled_year_edit
+    };
+    app.scheduled_month_edit = TextBox {
+        index: scheduled_month.len(),
+        text: scheduled_month,
+        ..app.scheduled_month_edit
+    };
+    app.scheduled_date_edit = TextBox {
+        index: scheduled_date.len(),
+        text: scheduled_date,
+        ..app.scheduled_date_edit
+    };
+
+    let estimate = match &app.task_list.current_taskdate {
+        TaskDate::Task(Task {
+            estimate_minutes: Some(m),
+            ..
+        }) if app.editing_task => format_minutes(*m),
+        _ => "".to_string(),
+    };
+
+    app.estimate_edit = TextBox {
+        index: estimate.len(),
+        text: estimate,
+        ..app.estimate_edit
+    };
     app.category_edit_state.select(Some(category_index));
     app.task_edit_field = SelectedField::Name;
-    app.keybind_hints = "Exit[esc/ctrl-c]".to_string();
+    app.keybind_hints = app.keymap.hints(CONTEXTS);
 
     AppReturn::Continue
 }
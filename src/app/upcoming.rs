@@ -1,17 +1,21 @@
 use crate::{
+    action::Action,
     app::{App, AppReturn},
     database::IOEvent,
-    key::Key,
     task::TaskDate,
+    tracking::Timer,
 };
 
-pub async fn do_action(app: &mut App, key: Key) -> AppReturn {
-    match key {
-        Key::Char('j') | Key::Down => {
+use super::selection;
+use chrono::Utc;
+
+pub async fn do_action(app: &mut App, action: Action) -> AppReturn {
+    match action {
+        Action::ScrollDown => {
             app.task_list_state
                 .select(Some(app.task_list_state.selected().unwrap() + 1));
         }
-        Key::Char('k') | Key::Up => {
+        Action::ScrollUp => {
             app.task_list_state.select(Some(
                 app.task_list_state
                     .selected()
@@ -19,25 +23,64 @@ pub async fn do_action(app: &mut App, key: Key) -> AppReturn {
                     .saturating_sub(1),
             ));
         }
-        Key::Char('r') => {
+        Action::ResetCursor => {
             app.task_list_state.select(Some(0));
         }
-        Key::Enter => {
-            if let TaskDate::Task(_) = app.task_list.current_taskdate {
-                let editing_task = &mut app.task_list.tasks[app.task_list.selected_index];
-                editing_task.completed = !editing_task.completed;
+        Action::CompleteTask => {
+            if let TaskDate::Task(t) = &app.task_list.current_taskdate {
+                let (id, action) = (
+                    t.id,
+                    if t.completed {
+                        "uncomplete"
+                    } else {
+                        "complete"
+                    },
+                );
+                selection::update_tasks(app, &[id], action, |t| t.completed = !t.completed).await;
+
+                // Completing a task also stops any time being tracked against it
+                let completed = app
+                    .task_list
+                    .tasks
+                    .iter()
+                    .any(|t| t.id == id && t.completed);
+                if completed && app.timer.as_ref().is_some_and(|t| t.task_id == id) {
+                    app.timer = None;
+                    app.dispatch(IOEvent::StopTimer(id)).await;
+                }
+            }
+        }
+        Action::DeleteTask => {
+            if let Some(id) = app.selected_task_id() {
+                selection::delete_tasks(app, &[id]).await;
+            }
+        }
+        Action::ToggleTimer => {
+            if let TaskDate::Task(t) = &app.task_list.current_taskdate {
+                let selected_id = t.id;
+
+                if let Some(running) = app.timer.take() {
+                    app.dispatch(IOEvent::StopTimer(running.task_id)).await;
+                    if running.task_id == selected_id {
+                        return AppReturn::Continue;
+                    }
+                }
 
-                let io_event = IOEvent::UpdateTask(editing_task.clone());
-                app.dispatch(io_event).await;
+                if !t.completed {
+                    app.timer = Some(Timer {
+                        task_id: selected_id,
+                        task_name: t.name.clone(),
+                        started_at: Utc::now(),
+                    });
+                    app.dispatch(IOEvent::StartTimer(selected_id)).await;
+                }
             }
         }
-        Key::Char('q') | Key::Esc | Key::Ctrl('c') => return AppReturn::Quit,
         _ => (),
     }
     AppReturn::Continue
 }
 
 pub fn initialize(app: &mut App) {
-    app.keybind_hints =
-        "Scroll[j/k]  [R]eset  [E]dit  [A]dd  Complete[Enter] [Q]uit[esc/ctrl-c]".to_string();
+    app.keybind_hints = app.keymap.hints(&app.active_contexts());
 }
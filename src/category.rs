@@ -2,4 +2,5 @@
 pub struct Category {
     pub name: String,
     pub id: i32,
+    pub color: Option<String>, // foreground color name or hex code, see theme::parse_color
 }
@@ -0,0 +1,52 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::io::{self, Write};
+
+use crate::task::Task;
+
+// Hands the text to the terminal in an OSC 52 escape sequence, which puts it on the system
+// clipboard even when running over SSH
+pub fn copy(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", STANDARD.encode(text))?;
+    stdout.flush()
+}
+
+// One task per line, e.g. "Read ch 4 (Physics, due Fri Oct 23)"
+pub fn as_text(tasks: &[Task]) -> String {
+    tasks
+        .iter()
+        .map(|t| format!("{} ({})", t.name, details(t)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// A checklist, e.g. "- [ ] Read ch 4 (Physics, due Fri Oct 23) #reading"
+pub fn as_markdown(tasks: &[Task]) -> String {
+    tasks
+        .iter()
+        .map(|t| {
+            let mut line = format!(
+                "- [{}] {} ({})",
+                if t.completed { "x" } else { " " },
+                escape_markdown(&t.name),
+                escape_markdown(&details(t))
+            );
+            t.tags.iter().for_each(|tag| line += &format!(" #{}", tag));
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn details(t: &Task) -> String {
+    format!("{}, due {}", t.category.name, t.due_date.format("%a %b %d"))
+}
+
+fn escape_markdown(text: &str) -> String {
+    text.chars()
+        .flat_map(|c| match c {
+            '\\' | '*' | '_' | '`' | '[' | ']' | '#' | '<' => vec!['\\', c],
+            c => vec![c],
+        })
+        .collect()
+}
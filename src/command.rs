@@ -0,0 +1,256 @@
+use chrono::NaiveDate;
+use std::num::IntErrorKind;
+
+use crate::{quick_add::parse_relative_date, theme};
+
+// Parsed ":" command line
+pub enum Command {
+    Add(String),
+    Done,
+    Move(MoveTarget),
+    RenameCategory(String),
+    ColorCategory(Option<String>), // None goes back to the theme's category color
+    Theme(String),
+    Filter(Option<String>),
+    Export(Option<String>),
+    Quit,
+}
+
+pub enum MoveTarget {
+    Shift(i64),
+    Date(NaiveDate),
+}
+
+// Command names offered by tab completion, with the subcommands they take
+const COMMANDS: [(&str, &[&str]); 8] = [
+    ("add", &[]),
+    ("done", &[]),
+    ("move", &["today", "tomorrow", "+1d", "+1w"]),
+    ("cat", &["rename", "color"]),
+    ("filter", &[]),
+    ("export", &[]),
+    ("theme", &theme::BUILT_IN),
+    ("quit", &[]),
+];
+
+pub fn parse(line: &str, today: NaiveDate) -> Result<Command, String> {
+    let line = line.trim();
+    let (name, args) = line.split_once(' ').unwrap_or((line, ""));
+    let args = args.trim();
+
+    match name {
+        "add" | "a" if !args.is_empty() => Ok(Command::Add(args.to_string())),
+        "add" | "a" => Err("usage: add <quick-add text>".to_string()),
+        "done" | "d" => Ok(Command::Done),
+        "move" | "m" => match parse_shift(args) {
+            Some(shift) => shift.map(|days| Command::Move(MoveTarget::Shift(days))),
+            None => parse_relative_date(args, today)
+                .map(|d| Command::Move(MoveTarget::Date(d)))
+                .ok_or("usage: move <+Nd|-Nw|date>".to_string()),
+        },
+        "cat" => match args.split_once(' ') {
+            Some(("rename", new_name)) if !new_name.trim().is_empty() => {
+                Ok(Command::RenameCategory(new_name.trim().to_string()))
+            }
+            Some(("color", "none")) => Ok(Command::ColorCategory(None)),
+            Some(("color", color)) => theme::parse_color(color.trim())
+                .map(|_| Command::ColorCategory(Some(color.trim().to_string()))),
+            _ => Err("usage: cat rename <name> | cat color <color|none>".to_string()),
+        },
+        "filter" | "f" => Ok(Command::Filter(
+            (!args.is_empty()).then(|| args.to_string()),
+        )),
+        "export" => Ok(Command::Export(
+            (!args.is_empty()).then(|| args.to_string()),
+        )),
+        "theme" if !args.is_empty() => Ok(Command::Theme(args.to_string())),
+        "theme" => Err("usage: theme <name>".to_string()),
+        "q" | "quit" => Ok(Command::Quit),
+        "" => Err("no command".to_string()),
+        _ => Err(format!("not a command: {}", name)),
+    }
+}
+
+// Signed day offsets like "+2d", "-1w" or "+3", or an error for ones too large to count
+fn parse_shift(arg: &str) -> Option<Result<i64, String>> {
+    if !arg.starts_with(['+', '-']) {
+        return None;
+    }
+    let (amount, days_per) = match arg.strip_suffix('w') {
+        Some(weeks) => (weeks, 7),
+        None => (arg.strip_suffix('d').unwrap_or(arg), 1),
+    };
+    let out_of_range = format!("shift out of range: {}", arg);
+    match amount.parse::<i64>() {
+        Ok(amount) => Some(amount.checked_mul(days_per).ok_or(out_of_range)),
+        Err(e)
+            if matches!(
+                e.kind(),
+                IntErrorKind::PosOverflow | IntErrorKind::NegOverflow
+            ) =>
+        {
+            Some(Err(out_of_range))
+        }
+        Err(_) => None,
+    }
+}
+
+// Completes the word being typed as far as it is unambiguous, returning the new line and the
+// candidates that still match
+pub fn complete(line: &str, category_names: &[String]) -> (String, Vec<String>) {
+    let (head, word) = match line.rsplit_once(' ') {
+        Some((head, word)) => (format!("{} ", head), word),
+        None => (String::new(), line),
+    };
+
+    let candidates: Vec<String> = if head.is_empty() {
+        COMMANDS.iter().map(|(name, _)| name.to_string()).collect()
+    } else if let Some(query) = word.strip_prefix('@') {
+        category_names
+            .iter()
+            .filter(|c| !c.contains(' '))
+            .map(|c| format!("@{}", c))
+            .filter(|c| c[1..].to_lowercase().starts_with(&query.to_lowercase()))
+            .collect()
+    } else {
+        let command = head.split_whitespace().next().unwrap_or_default();
+        COMMANDS
+            .iter()
+            .find(|(name, _)| *name == command)
+            .map(|(_, subcommands)| subcommands.iter().map(|s| s.to_string()).collect())
+            .unwrap_or_default()
+    };
+
+    let matching: Vec<String> = candidates
+        .into_iter()
+        .filter(|c| c.to_lowercase().starts_with(&word.to_lowercase()))
+        .collect();
+
+    let completed = match matching.as_slice() {
+        [] => word.to_string(),
+        [only] => format!("{} ", only),
+        [first, rest @ ..] => {
+            let mut prefix = first.clone();
+            for candidate in rest {
+                while !candidate.to_lowercase().starts_with(&prefix.to_lowercase()) {
+                    prefix.pop();
+                }
+            }
+            if prefix.len() < word.len() {
+                word.to_string()
+            } else {
+                prefix
+            }
+        }
+    };
+
+    (format!("{}{}", head, completed), matching)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 10, 16).unwrap()
+    }
+
+    fn shift(line: &str) -> Option<i64> {
+        match parse(line, today()) {
+            Ok(Command::Move(MoveTarget::Shift(days))) => Some(days),
+            _ => None,
+        }
+    }
+
+    fn parsed(line: &str) -> Command {
+        match parse(line, today()) {
+            Ok(command) => command,
+            Err(e) => panic!("\"{}\" should parse: {}", line, e),
+        }
+    }
+
+    fn error(line: &str) -> String {
+        match parse(line, today()) {
+            Err(e) => e,
+            Ok(_) => panic!("\"{}\" should not parse", line),
+        }
+    }
+
+    #[test]
+    fn move_shifts() {
+        assert_eq!(shift("move +2d"), Some(2));
+        assert_eq!(shift("m -1w"), Some(-7));
+        assert_eq!(shift("move +3"), Some(3));
+        assert_eq!(shift("move -0"), Some(0));
+    }
+
+    #[test]
+    fn move_shifts_out_of_range() {
+        assert_eq!(
+            error("move +9999999999999999999d"),
+            "shift out of range: +9999999999999999999d"
+        );
+        assert_eq!(
+            error("move -9223372036854775807w"),
+            "shift out of range: -9223372036854775807w"
+        );
+    }
+
+    #[test]
+    fn move_to_date() {
+        let tomorrow = NaiveDate::from_ymd_opt(2024, 10, 17).unwrap();
+        assert!(
+            matches!(parsed("move tomorrow"), Command::Move(MoveTarget::Date(d)) if d == tomorrow)
+        );
+        assert_eq!(error("move"), "usage: move <+Nd|-Nw|date>");
+        assert_eq!(error("move +x"), "usage: move <+Nd|-Nw|date>");
+        assert_eq!(error("move +-3d"), "usage: move <+Nd|-Nw|date>");
+    }
+
+    #[test]
+    fn other_commands() {
+        assert!(matches!(parsed("  add Lab @Physics "), Command::Add(a) if a == "Lab @Physics"));
+        assert!(matches!(parsed("d"), Command::Done));
+        assert!(matches!(parsed("cat rename  Chem "), Command::RenameCategory(n) if n == "Chem"));
+        assert!(matches!(
+            parsed("cat color none"),
+            Command::ColorCategory(None)
+        ));
+        assert!(matches!(parsed("filter"), Command::Filter(None)));
+        assert!(matches!(parsed("f #lab"), Command::Filter(Some(f)) if f == "#lab"));
+        assert!(matches!(parsed("q"), Command::Quit));
+        assert_eq!(error("add"), "usage: add <quick-add text>");
+        assert_eq!(
+            error("cat rename"),
+            "usage: cat rename <name> | cat color <color|none>"
+        );
+        assert_eq!(error(""), "no command");
+        assert_eq!(error("frobnicate now"), "not a command: frobnicate");
+    }
+
+    #[test]
+    fn completes_commands() {
+        assert_eq!(
+            complete("ex", &[]),
+            ("export ".to_string(), vec!["export".to_string()])
+        );
+        assert_eq!(complete("zz", &[]), ("zz".to_string(), vec![]));
+
+        let (line, candidates) = complete("move t", &[]);
+        assert_eq!(line, "move to");
+        assert_eq!(candidates, vec!["today", "tomorrow"]);
+    }
+
+    #[test]
+    fn completes_categories() {
+        let names = vec![
+            "Physics".to_string(),
+            "Phys Ed".to_string(),
+            "Math".to_string(),
+        ];
+
+        let (line, candidates) = complete("add Lab @ph", &names);
+        assert_eq!(line, "add Lab @Physics ");
+        assert_eq!(candidates, vec!["@Physics"]);
+    }
+}
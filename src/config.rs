@@ -0,0 +1,67 @@
+use serde::Deserialize;
+use std::{collections::HashMap, env, error, fs, path::PathBuf};
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub daily_capacity_minutes: i32, // date headers with more estimated work than this are highlighted
+    pub pomodoro_work_minutes: i64,
+    pub pomodoro_short_break_minutes: i64,
+    pub pomodoro_long_break_minutes: i64,
+    pub pomodoros_before_long_break: u32,
+    pub refresh_interval_seconds: u64, // background reload period, 0 to only reload at midnight
+    pub key_sequence_timeout_ms: u64,  // how long to wait for the rest of a binding like gg
+    pub mouse: bool,                   // whether mouse capture starts on, it can be toggled later
+    pub toast_seconds: u64,            // how long messages pop up over the view, 0 to not show them
+    pub detail_pane: bool,             // whether the selected task's details start out shown
+    pub detail_pane_position: PanePosition,
+    pub theme: String, // a built-in theme or one of the themes below
+    pub themes: HashMap<String, HashMap<String, String>>, // custom themes, style name to style
+}
+
+// Side of the upcoming view the detail pane goes on. On the right it moves to the bottom if that
+// would leave too little room for the list
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PanePosition {
+    Right,
+    Bottom,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            daily_capacity_minutes: 360,
+            pomodoro_work_minutes: 25,
+            pomodoro_short_break_minutes: 5,
+            pomodoro_long_break_minutes: 15,
+            pomodoros_before_long_break: 4,
+            refresh_interval_seconds: 60,
+            key_sequence_timeout_ms: 1000,
+            mouse: true,
+            toast_seconds: 4,
+            detail_pane: false,
+            detail_pane_position: PanePosition::Right,
+            theme: "dark".to_string(),
+            themes: HashMap::new(),
+        }
+    }
+}
+
+impl Config {
+    // Reads config.toml from the config directory, falling back to defaults when there isn't one
+    pub fn load() -> Result<Self, Box<dyn error::Error>> {
+        match config_dir().map(|d| d.join("config.toml")) {
+            Some(path) if path.exists() => Ok(toml::from_str(&fs::read_to_string(path)?)?),
+            _ => Ok(Config::default()),
+        }
+    }
+}
+
+// $XDG_CONFIG_HOME/tuitask, or ~/.config/tuitask if that isn't set
+pub fn config_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))
+        .map(|d| d.join("tuitask"))
+}
@@ -1,32 +1,106 @@
-use crate::{app::App, category::Category, task::Task};
-use sqlx::Row;
-use std::{collections::HashMap, sync::Arc};
+use crate::{
+    app::App,
+    category::Category,
+    task::{Conflict, Task, TaskEvent, TaskField, TaskTimestamps},
+    tracking::{Stats, TimeTotal, Timer},
+    undo::UndoEntry,
+};
+use chrono::{DateTime, Utc};
+use sqlx::{
+    postgres::{PgListener, PgRow},
+    Postgres, Row, Transaction,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+use tokio::sync::mpsc;
 
+#[derive(Clone)]
 pub enum IOEvent {
     LoadData,
+    RefreshData,
     UpdateTask(Task),
+    UpdateTasks(Vec<Task>),
     CreateTask(Task),
+    DeleteTasks(Vec<Task>), // as last seen, so deletes don't go over other clients' edits
+    RestoreTasks(Vec<Task>),
+    AddDependency(i32, i32),
+    RemoveDependency(i32, i32),
+    StartTimer(i32),
+    StopTimer(i32),
+    LogPomodoro(i32, DateTime<Utc>, DateTime<Utc>),
+    LoadStats,
+    LoadHistory(i32),
+    LoadTimestamps(i32),
+    ExportTasks(String), // path of the csv file
     UpdateCategory(Category),
     CreateCategory(String),
+    DeleteCategory(i32),
+    RestoreCategory(Category),
+    Status(String), // shown once everything queued before it has finished
+}
+
+struct LoadedData {
+    tasks: Vec<Task>,
+    categories: Vec<Category>,
+    dependencies: Vec<(i32, i32)>,
+    timer: Option<Timer>,
+}
+
+// Time tracked on a deleted task, which the delete cascades away, kept for undo to put back
+#[derive(Default)]
+struct DeletedTracking {
+    time_entries: Vec<(DateTime<Utc>, DateTime<Utc>)>,
+    pomodoros: Vec<(DateTime<Utc>, DateTime<Utc>)>,
 }
 
 pub struct IOHandler {
     app: Arc<tokio::sync::Mutex<App>>,
     pub db_pool: sqlx::PgPool,
+    own_versions: HashMap<i32, HashSet<i32>>, // task versions written by this client
+    deleted_tracking: HashMap<i32, DeletedTracking>, // by task id, until the task is restored
 }
 
 impl IOHandler {
     pub fn new(app: Arc<tokio::sync::Mutex<App>>, db_pool: sqlx::PgPool) -> Self {
-        Self { app, db_pool }
+        Self {
+            app,
+            db_pool,
+            own_versions: HashMap::new(),
+            deleted_tracking: HashMap::new(),
+        }
     }
 
     pub async fn handle_io(&mut self, io_event: IOEvent) -> Result<(), Box<dyn std::error::Error>> {
         match io_event {
             IOEvent::LoadData => self.load_data().await?,
-            IOEvent::UpdateTask(t) => self.update_task(t).await?,
+            IOEvent::RefreshData => self.refresh_data().await?,
+            IOEvent::UpdateTask(t) => self.update_tasks(vec![t]).await?,
+            IOEvent::UpdateTasks(tasks) => self.update_tasks(tasks).await?,
             IOEvent::CreateTask(t) => self.create_task(t).await?,
+            IOEvent::DeleteTasks(tasks) => self.delete_tasks(tasks).await?,
+            IOEvent::RestoreTasks(tasks) => self.restore_tasks(tasks).await?,
+            IOEvent::AddDependency(blocker, blocked) => {
+                self.add_dependency(blocker, blocked).await?
+            }
+            IOEvent::RemoveDependency(blocker, blocked) => {
+                self.remove_dependency(blocker, blocked).await?
+            }
+            IOEvent::StartTimer(task_id) => self.start_timer(task_id).await?,
+            IOEvent::StopTimer(task_id) => self.stop_timer(task_id).await?,
+            IOEvent::LogPomodoro(task_id, started_at, ended_at) => {
+                self.log_pomodoro(task_id, started_at, ended_at).await?
+            }
+            IOEvent::LoadStats => self.load_stats().await?,
+            IOEvent::LoadHistory(task_id) => self.load_history(task_id).await?,
+            IOEvent::LoadTimestamps(task_id) => self.load_timestamps(task_id).await?,
+            IOEvent::ExportTasks(path) => self.export_tasks(path).await?,
             IOEvent::UpdateCategory(c) => self.update_category(c).await?,
             IOEvent::CreateCategory(name) => self.create_category(name).await?,
+            IOEvent::DeleteCategory(id) => self.delete_category(id).await?,
+            IOEvent::RestoreCategory(c) => self.restore_category(c).await?,
+            IOEvent::Status(s) => self.app.lock().await.status.info(s),
         };
 
         Ok(())
@@ -36,13 +110,73 @@ impl IOHandler {
     async fn load_data(&mut self) -> Result<(), sqlx::Error> {
         self.update_status("loading data".to_string()).await;
 
-        let category_rows = sqlx::query("SELECT * FROM category")
+        let data = self.fetch_data().await?;
+
+        let mut app = self.app.lock().await;
+        app.task_list.tasks = data.tasks;
+        app.task_list.dependencies = data.dependencies;
+        app.timer = data.timer;
+        app.categories = data.categories;
+        app.status.info("data loaded");
+
+        Ok(())
+    }
+
+    // Reloads everything in the background, keeping tasks completed locally but not yet purged
+    // and keeping the cursor on the same task/category even if rows moved around it
+    async fn refresh_data(&mut self) -> Result<(), sqlx::Error> {
+        let mut data = self.fetch_data().await?;
+
+        let mut app = self.app.lock().await;
+        let selected_task = app.selected_task_id();
+        let selected_category = app
+            .categories
+            .get(app.category_list_state.selected().unwrap_or(0))
+            .map(|c| c.id);
+
+        let pending_completions: Vec<Task> = app
+            .task_list
+            .tasks
+            .iter()
+            .filter(|t| t.completed)
+            .cloned()
+            .collect();
+        data.tasks
+            .retain(|t| !pending_completions.iter().any(|p| p.id == t.id));
+        data.tasks.extend(pending_completions);
+
+        app.task_list.tasks = data.tasks;
+        app.task_list.dependencies = data.dependencies;
+        app.timer = data.timer;
+        app.categories = data.categories;
+
+        // Editors act on the selected task, so don't leave one open on a task that's gone
+        if let Some(id) = selected_task {
+            if app.task_list.tasks.iter().any(|t| t.id == id) {
+                app.reselect_task(id);
+            } else if app.pop_up.is_some() {
+                app.close_stale_pop_up("task");
+            }
+        }
+        let category_position =
+            selected_category.and_then(|id| app.categories.iter().position(|c| c.id == id));
+        if category_position.is_none() && app.renaming_category() {
+            app.close_stale_pop_up("category");
+        }
+        app.category_list_state
+            .select(Some(category_position.unwrap_or(0)));
+
+        Ok(())
+    }
+
+    async fn fetch_data(&self) -> Result<LoadedData, sqlx::Error> {
+        let category_rows = sqlx::query("SELECT * FROM category ORDER BY id")
             .fetch_all(&self.db_pool)
             .await?;
 
-        let categories: HashMap<i32, String> = category_rows
+        let categories: HashMap<i32, Category> = category_rows
             .iter()
-            .map(|r| (r.get("id"), r.get("name")))
+            .map(|r| (r.get("id"), category_from_row(r)))
             .collect();
 
         let task_rows = sqlx::query("SELECT * FROM task WHERE completed = FALSE")
@@ -51,72 +185,587 @@ impl IOHandler {
 
         let task_list: Vec<Task> = task_rows
             .iter()
-            .map(|r| Task {
-                id: r.get("id"),
-                name: r.get("name"),
-                due_date: r.get("due_date"),
-                completed: r.get("completed"),
-                category: {
-                    let category_id: i32 = r.get("category_id");
-                    Category {
-                        name: categories.get(&category_id).unwrap().to_string(),
-                        id: category_id,
-                    }
-                },
+            .map(|r| {
+                let category_id: i32 = r.get("category_id");
+                task_from_row(r, categories.get(&category_id).unwrap().clone())
             })
             .collect();
 
-        let mut app = self.app.lock().await;
-        app.task_list.tasks = task_list;
-        app.categories = categories
+        let dependency_rows = sqlx::query("SELECT * FROM task_dependency")
+            .fetch_all(&self.db_pool)
+            .await?;
+
+        let dependencies: Vec<(i32, i32)> = dependency_rows
             .iter()
-            .map(|c| Category {
-                id: *c.0,
-                name: c.1.to_string(),
-            })
+            .map(|r| (r.get("blocker_id"), r.get("blocked_id")))
             .collect();
-        app.status_text = "data loaded".to_string();
 
-        Ok(())
+        // Resume a timer left running by a previous session
+        let timer = sqlx::query(
+            "SELECT e.task_id, e.started_at, t.name FROM time_entry e JOIN task t ON t.id = e.task_id \
+            WHERE e.ended_at IS NULL ORDER BY e.started_at DESC LIMIT 1",
+        )
+        .fetch_optional(&self.db_pool)
+        .await?
+        .map(|r| Timer {
+            task_id: r.get("task_id"),
+            task_name: r.get("name"),
+            started_at: r.get("started_at"),
+        });
+
+        Ok(LoadedData {
+            tasks: task_list,
+            categories: category_rows.iter().map(category_from_row).collect(),
+            dependencies,
+            timer,
+        })
     }
 
-    async fn update_task(&mut self, t: Task) -> Result<(), sqlx::Error> {
-        self.update_status("updating task".to_string()).await;
+    // Only applies each update if nobody else changed the task since it was loaded. Versions
+    // written by this client are skipped over, since quick successive edits are sent with the same
+    // version. A batch is applied per task rather than all or nothing: tasks another client changed
+    // (which go to the conflict resolver) or deleted are skipped, the rest are committed together
+    // and the skipped ones are named in the message log
+    async fn update_tasks(&mut self, tasks: Vec<Task>) -> Result<(), sqlx::Error> {
+        self.update_status("updating tasks".to_string()).await;
+
+        let ids: Vec<i32> = tasks.iter().map(|t| t.id).collect();
+        let mut transaction = self.db_pool.begin().await?;
+        let old_tasks = lock_tasks(&mut transaction, &ids).await?;
+
+        let mut versions = Vec::new();
+        let mut conflicts = Vec::new();
+        let mut deleted = Vec::new();
+        for t in tasks {
+            let Some(old) = old_tasks.iter().find(|old| old.id == t.id) else {
+                deleted.push((t.id, t.name));
+                continue;
+            };
+
+            if self.changed_elsewhere(&t, old) {
+                conflicts.push(Conflict::new(t, old.clone()));
+                continue;
+            }
 
-        sqlx::query("UPDATE task SET name = $1, due_date = $2, completed = $3, category_id = $4 WHERE id = $5")
-            .bind(t.name)
+            let new_version: i32 = sqlx::query(
+                "UPDATE task SET name = $1, due_date = $2, completed = $3, category_id = $4, scheduled_date = $5, estimate_minutes = $6, priority = $7, tags = $8, version = version + 1 \
+                WHERE id = $9 RETURNING version",
+            )
+            .bind(t.name.clone())
             .bind(t.due_date)
             .bind(t.completed)
             .bind(t.category.id)
+            .bind(t.scheduled_date)
+            .bind(t.estimate_minutes)
+            .bind(t.priority)
+            .bind(t.tags.clone())
             .bind(t.id)
-            .execute(&self.db_pool)
-            .await?;
+            .fetch_one(&mut *transaction)
+            .await?
+            .get("version");
 
-        self.update_status("update successful".to_string()).await;
+            log_task_events(&mut transaction, t.id, Some(old), Some(&t)).await?;
+            versions.push((t.id, new_version));
+        }
+        transaction.commit().await?;
+
+        self.record_own_versions(&versions).await;
+
+        let mut app = self.app.lock().await;
+        deleted.iter().for_each(|(id, _)| app.task_list.remove(*id));
+        let skipped: Vec<String> = conflicts
+            .iter()
+            .map(|c| format!("{} (changed by another client)", c.mine.name))
+            .chain(
+                deleted
+                    .iter()
+                    .map(|(_, name)| format!("{} (deleted by another client)", name)),
+            )
+            .collect();
+        if versions.is_empty() && skipped.len() == 1 {
+            match conflicts.is_empty() {
+                true => app.status.warn("task was deleted by another client"),
+                false => app
+                    .status
+                    .warn("edit conflict, task was changed by another client"),
+            }
+        } else if !skipped.is_empty() {
+            app.status.warn(format!(
+                "updated {} of {} tasks, skipped {}",
+                versions.len(),
+                versions.len() + skipped.len(),
+                skipped.join(", ")
+            ));
+        } else if versions.len() == 1 {
+            app.status.info("update successful");
+        } else {
+            app.status.info(format!("updated {} tasks", versions.len()));
+        }
+        // A new conflict brings the resolver back up even if earlier ones were put off
+        if !conflicts.is_empty() {
+            app.conflicts_dismissed = false;
+        }
+        app.conflicts.extend(conflicts);
 
         Ok(())
     }
 
+    // Remembers versions this client wrote and bumps the local copies to match
+    // Whether another client wrote a version of the task after the one this client last saw
+    fn changed_elsewhere(&self, seen: &Task, current: &Task) -> bool {
+        let own_versions = self.own_versions.get(&seen.id);
+        !(seen.version + 1..=current.version)
+            .all(|v| own_versions.is_some_and(|own| own.contains(&v)))
+    }
+
+    async fn record_own_versions(&mut self, versions: &[(i32, i32)]) {
+        let mut app = self.app.lock().await;
+        for (id, version) in versions {
+            self.own_versions.entry(*id).or_default().insert(*version);
+            if let Some(local) = app.task_list.tasks.iter_mut().find(|t| t.id == *id) {
+                local.version = local.version.max(*version);
+            }
+        }
+    }
+
     async fn create_task(&mut self, t: Task) -> Result<(), sqlx::Error> {
         self.update_status("creating task".to_string()).await;
 
+        let mut transaction = self.db_pool.begin().await?;
         let created_task_id = sqlx::query(
-            "INSERT INTO task (name, due_date, category_id) VALUES ($1, $2, $3) RETURNING id",
+            "INSERT INTO task (name, due_date, category_id, scheduled_date, estimate_minutes, priority, tags) VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING id",
         )
         .bind(t.name.clone())
         .bind(t.due_date)
         .bind(t.category.id)
-        .fetch_one(&self.db_pool)
+        .bind(t.scheduled_date)
+        .bind(t.estimate_minutes)
+        .bind(t.priority)
+        .bind(t.tags.clone())
+        .fetch_one(&mut *transaction)
         .await?
         .get("id");
 
-        let mut app = self.app.lock().await;
-        app.task_list.tasks.push(Task {
+        let created_task = Task {
             id: created_task_id,
             ..t
+        };
+        log_task_events(&mut transaction, created_task_id, None, Some(&created_task)).await?;
+        transaction.commit().await?;
+
+        // Upserted since the change notification for this insert may have been applied already
+        let mut app = self.app.lock().await;
+        app.task_list.upsert(created_task.clone());
+
+        // Recorded here rather than in the editor since undoing needs the id assigned above
+        app.undo_history.record(UndoEntry {
+            description: format!("create {}", created_task.name),
+            undo: vec![IOEvent::DeleteTasks(vec![created_task.clone()])],
+            redo: vec![IOEvent::RestoreTasks(vec![created_task])],
         });
         app.task_list.tasks.sort();
-        app.status_text = "task created".to_string();
+        app.status.info("task created");
+
+        Ok(())
+    }
+
+    // Tasks changed by another client since they were last seen are kept and put back in the list
+    async fn delete_tasks(&mut self, tasks: Vec<Task>) -> Result<(), sqlx::Error> {
+        self.update_status("deleting tasks".to_string()).await;
+
+        let ids: Vec<i32> = tasks.iter().map(|t| t.id).collect();
+        let mut transaction = self.db_pool.begin().await?;
+        let mut deleted = Vec::new();
+        let mut changed = Vec::new();
+        for old in lock_tasks(&mut transaction, &ids).await? {
+            match tasks.iter().find(|t| t.id == old.id) {
+                Some(seen) if self.changed_elsewhere(seen, &old) => changed.push(old),
+                _ => {
+                    log_task_events(&mut transaction, old.id, Some(&old), None).await?;
+                    deleted.push(old.id);
+                }
+            }
+        }
+        // A running timer is stopped by the delete, so its entry is kept as ending now
+        let time_entries = sqlx::query(
+            "SELECT task_id, started_at, COALESCE(ended_at, now()) AS ended_at FROM time_entry WHERE task_id = ANY($1)",
+        )
+        .bind(&deleted)
+        .fetch_all(&mut *transaction)
+        .await?;
+        let pomodoros = sqlx::query(
+            "SELECT task_id, started_at, ended_at FROM pomodoro WHERE task_id = ANY($1)",
+        )
+        .bind(&deleted)
+        .fetch_all(&mut *transaction)
+        .await?;
+        sqlx::query("DELETE FROM task WHERE id = ANY($1)")
+            .bind(&deleted)
+            .execute(&mut *transaction)
+            .await?;
+        transaction.commit().await?;
+
+        for r in time_entries {
+            self.deleted_tracking
+                .entry(r.get("task_id"))
+                .or_default()
+                .time_entries
+                .push((r.get("started_at"), r.get("ended_at")));
+        }
+        for r in pomodoros {
+            self.deleted_tracking
+                .entry(r.get("task_id"))
+                .or_default()
+                .pomodoros
+                .push((r.get("started_at"), r.get("ended_at")));
+        }
+
+        let mut app = self.app.lock().await;
+        changed.iter().for_each(|t| app.task_list.upsert(t.clone()));
+        app.task_list.tasks.sort();
+        let skipped: Vec<String> = changed
+            .iter()
+            .map(|t| format!("{} (changed by another client)", t.name))
+            .collect();
+        if deleted.is_empty() && skipped.len() == 1 {
+            app.status
+                .warn("task was changed by another client, not deleted");
+        } else if !skipped.is_empty() {
+            app.status.warn(format!(
+                "deleted {} of {} tasks, skipped {}",
+                deleted.len(),
+                deleted.len() + skipped.len(),
+                skipped.join(", ")
+            ));
+        } else if tasks.len() == 1 {
+            app.status.info("task deleted");
+        } else {
+            app.status.info(format!("deleted {} tasks", tasks.len()));
+        }
+
+        Ok(())
+    }
+
+    // Re-inserts deleted tasks under their old ids, skipping any id that is somehow back in use
+    async fn restore_tasks(&mut self, tasks: Vec<Task>) -> Result<(), sqlx::Error> {
+        self.update_status("restoring tasks".to_string()).await;
+
+        let mut transaction = self.db_pool.begin().await?;
+        let mut versions = Vec::new();
+        for t in &tasks {
+            let restored = sqlx::query(
+                "INSERT INTO task (id, name, due_date, completed, category_id, scheduled_date, estimate_minutes, priority, tags, version) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) \
+                ON CONFLICT (id) DO NOTHING RETURNING id",
+            )
+            .bind(t.id)
+            .bind(t.name.clone())
+            .bind(t.due_date)
+            .bind(t.completed)
+            .bind(t.category.id)
+            .bind(t.scheduled_date)
+            .bind(t.estimate_minutes)
+            .bind(t.priority)
+            .bind(t.tags.clone())
+            .bind(t.version + 1)
+            .fetch_optional(&mut *transaction)
+            .await?
+            .is_some();
+
+            if restored {
+                log_task_events(&mut transaction, t.id, None, Some(t)).await?;
+                versions.push((t.id, t.version + 1));
+
+                let tracking = self.deleted_tracking.remove(&t.id).unwrap_or_default();
+                for (started_at, ended_at) in tracking.time_entries {
+                    sqlx::query(
+                        "INSERT INTO time_entry (task_id, started_at, ended_at) VALUES ($1, $2, $3)",
+                    )
+                    .bind(t.id)
+                    .bind(started_at)
+                    .bind(ended_at)
+                    .execute(&mut *transaction)
+                    .await?;
+                }
+                for (started_at, ended_at) in tracking.pomodoros {
+                    sqlx::query(
+                        "INSERT INTO pomodoro (task_id, started_at, ended_at) VALUES ($1, $2, $3)",
+                    )
+                    .bind(t.id)
+                    .bind(started_at)
+                    .bind(ended_at)
+                    .execute(&mut *transaction)
+                    .await?;
+                }
+            }
+        }
+        transaction.commit().await?;
+
+        self.record_own_versions(&versions).await;
+        self.app.lock().await.status.info(if tasks.len() == 1 {
+            "task restored".to_string()
+        } else {
+            format!("restored {} tasks", tasks.len())
+        });
+
+        Ok(())
+    }
+
+    // Inserts the dependency only if it doesn't close a cycle, re-checked here in case another
+    // client linked the tasks the other way around since the last load
+    // Skipped rather than failing if either task is gone, which can happen when undo replays a
+    // dependency after another client deleted one of its tasks
+    async fn add_dependency(&mut self, blocker: i32, blocked: i32) -> Result<(), sqlx::Error> {
+        self.update_status("adding dependency".to_string()).await;
+
+        let inserted = sqlx::query(
+            "INSERT INTO task_dependency (blocker_id, blocked_id) \
+            SELECT $1, $2 \
+            WHERE EXISTS (SELECT 1 FROM task WHERE id = $1) \
+            AND EXISTS (SELECT 1 FROM task WHERE id = $2) \
+            AND NOT EXISTS ( \
+                WITH RECURSIVE downstream (id) AS ( \
+                    SELECT $2 \
+                    UNION SELECT d.blocked_id FROM task_dependency d JOIN downstream ON d.blocker_id = downstream.id \
+                ) SELECT 1 FROM downstream WHERE id = $1 \
+            ) ON CONFLICT DO NOTHING",
+        )
+        .bind(blocker)
+        .bind(blocked)
+        .execute(&self.db_pool)
+        .await?
+        .rows_affected();
+
+        if inserted == 0 {
+            let tasks_found: i64 =
+                sqlx::query("SELECT count(*) AS found FROM task WHERE id = $1 OR id = $2")
+                    .bind(blocker)
+                    .bind(blocked)
+                    .fetch_one(&self.db_pool)
+                    .await?
+                    .get("found");
+
+            let mut app = self.app.lock().await;
+            app.task_list
+                .dependencies
+                .retain(|d| *d != (blocker, blocked));
+            if tasks_found < 2 {
+                app.status
+                    .warn("dependency skipped, task was deleted by another client");
+            } else {
+                app.status.warn("dependency would create a cycle");
+            }
+        } else {
+            self.app.lock().await.status.info("dependency added");
+        }
+
+        Ok(())
+    }
+
+    async fn remove_dependency(&mut self, blocker: i32, blocked: i32) -> Result<(), sqlx::Error> {
+        self.update_status("removing dependency".to_string()).await;
+
+        sqlx::query("DELETE FROM task_dependency WHERE blocker_id = $1 AND blocked_id = $2")
+            .bind(blocker)
+            .bind(blocked)
+            .execute(&self.db_pool)
+            .await?;
+
+        self.app.lock().await.status.info("dependency removed");
+
+        Ok(())
+    }
+
+    async fn start_timer(&mut self, task_id: i32) -> Result<(), sqlx::Error> {
+        self.update_status("starting timer".to_string()).await;
+
+        let started_at =
+            sqlx::query("INSERT INTO time_entry (task_id) VALUES ($1) RETURNING started_at")
+                .bind(task_id)
+                .fetch_one(&self.db_pool)
+                .await?
+                .get("started_at");
+
+        let mut app = self.app.lock().await;
+        if let Some(timer) = app.timer.as_mut().filter(|t| t.task_id == task_id) {
+            timer.started_at = started_at;
+        }
+        app.status.info("timer started");
+
+        Ok(())
+    }
+
+    // Closes every open entry for the task rather than a specific one, so a stop queued right
+    // after a start doesn't need to wait for the new entry's id
+    async fn stop_timer(&mut self, task_id: i32) -> Result<(), sqlx::Error> {
+        self.update_status("stopping timer".to_string()).await;
+
+        sqlx::query(
+            "UPDATE time_entry SET ended_at = now() WHERE task_id = $1 AND ended_at IS NULL",
+        )
+        .bind(task_id)
+        .execute(&self.db_pool)
+        .await?;
+
+        self.app.lock().await.status.info("timer stopped");
+
+        Ok(())
+    }
+
+    async fn log_pomodoro(
+        &mut self,
+        task_id: i32,
+        started_at: DateTime<Utc>,
+        ended_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("INSERT INTO pomodoro (task_id, started_at, ended_at) VALUES ($1, $2, $3)")
+            .bind(task_id)
+            .bind(started_at)
+            .bind(ended_at)
+            .execute(&self.db_pool)
+            .await?;
+
+        self.app.lock().await.status.info("pomodoro logged");
+
+        Ok(())
+    }
+
+    // Totals tracked time (counting running entries up to now) and finished pomodoros per task
+    // and per category
+    async fn load_stats(&mut self) -> Result<(), sqlx::Error> {
+        self.update_status("loading stats".to_string()).await;
+
+        let task_rows = sqlx::query(
+            "SELECT t.name, \
+                COALESCE(EXTRACT(EPOCH FROM (SELECT SUM(COALESCE(e.ended_at, now()) - e.started_at) FROM time_entry e WHERE e.task_id = t.id))::bigint, 0) AS seconds, \
+                (SELECT COUNT(*) FROM pomodoro p WHERE p.task_id = t.id) AS pomodoros \
+            FROM task t \
+            WHERE EXISTS (SELECT 1 FROM time_entry e WHERE e.task_id = t.id) \
+                OR EXISTS (SELECT 1 FROM pomodoro p WHERE p.task_id = t.id) \
+            ORDER BY seconds DESC, pomodoros DESC",
+        )
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        let category_rows = sqlx::query(
+            "SELECT c.name, \
+                COALESCE(EXTRACT(EPOCH FROM (SELECT SUM(COALESCE(e.ended_at, now()) - e.started_at) FROM time_entry e JOIN task t ON t.id = e.task_id WHERE t.category_id = c.id))::bigint, 0) AS seconds, \
+                (SELECT COUNT(*) FROM pomodoro p JOIN task t ON t.id = p.task_id WHERE t.category_id = c.id) AS pomodoros \
+            FROM category c \
+            WHERE EXISTS (SELECT 1 FROM time_entry e JOIN task t ON t.id = e.task_id WHERE t.category_id = c.id) \
+                OR EXISTS (SELECT 1 FROM pomodoro p JOIN task t ON t.id = p.task_id WHERE t.category_id = c.id) \
+            ORDER BY seconds DESC, pomodoros DESC",
+        )
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        let to_totals = |rows: Vec<PgRow>| -> Vec<TimeTotal> {
+            rows.iter()
+                .map(|r| TimeTotal {
+                    name: r.get("name"),
+                    seconds: r.get("seconds"),
+                    pomodoros: r.get("pomodoros"),
+                })
+                .collect()
+        };
+
+        let mut app = self.app.lock().await;
+        app.stats = Stats {
+            by_task: to_totals(task_rows),
+            by_category: to_totals(category_rows),
+        };
+        app.status.show("stats loaded");
+
+        Ok(())
+    }
+
+    async fn load_history(&mut self, task_id: i32) -> Result<(), sqlx::Error> {
+        self.update_status("loading history".to_string()).await;
+
+        let history = sqlx::query(
+            "SELECT occurred_at, kind, field, old_value, new_value FROM task_event WHERE task_id = $1 ORDER BY occurred_at DESC, id",
+        )
+        .bind(task_id)
+        .fetch_all(&self.db_pool)
+        .await?
+        .iter()
+        .map(|r| TaskEvent {
+            occurred_at: r.get("occurred_at"),
+            kind: r.get("kind"),
+            field: r.get("field"),
+            old_value: r.get("old_value"),
+            new_value: r.get("new_value"),
+        })
+        .collect();
+
+        let mut app = self.app.lock().await;
+        app.history = history;
+        app.status.show("history loaded");
+
+        Ok(())
+    }
+
+    // Loaded quietly since it happens whenever the cursor moves with the detail pane open
+    async fn load_timestamps(&mut self, task_id: i32) -> Result<(), sqlx::Error> {
+        let row = sqlx::query(
+            "SELECT min(occurred_at) FILTER (WHERE kind = 'create') AS created_at, max(occurred_at) AS updated_at, max(occurred_at) FILTER (WHERE field = 'Completed' AND new_value = 'true') AS completed_at FROM task_event WHERE task_id = $1",
+        )
+        .bind(task_id)
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        let mut app = self.app.lock().await;
+        app.task_timestamps = Some(TaskTimestamps {
+            task_id,
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            completed_at: row.get("completed_at"),
+        });
+
+        Ok(())
+    }
+
+    async fn export_tasks(&mut self, path: String) -> Result<(), sqlx::Error> {
+        self.update_status("exporting tasks".to_string()).await;
+
+        let tasks: Vec<Task> = sqlx::query(
+            "SELECT t.*, c.name AS category_name, c.color AS category_color FROM task t JOIN category c ON c.id = t.category_id ORDER BY t.id",
+        )
+        .fetch_all(&self.db_pool)
+        .await?
+        .iter()
+        .map(|r| task_from_row(r, joined_category(r)))
+        .collect();
+
+        let mut csv =
+            "id,name,category,due_date,scheduled_date,estimate_minutes,priority,tags,completed\n"
+                .to_string();
+        for t in &tasks {
+            let fields = [
+                t.id.to_string(),
+                t.name.clone(),
+                t.category.name.clone(),
+                t.due_date.to_string(),
+                t.scheduled_date.map(|d| d.to_string()).unwrap_or_default(),
+                t.estimate_minutes
+                    .map(|m| m.to_string())
+                    .unwrap_or_default(),
+                t.priority.to_string(),
+                t.tags.join(" "),
+                t.completed.to_string(),
+            ];
+            csv += &fields.map(|f| csv_field(&f)).join(",");
+            csv += "\n";
+        }
+
+        // A bad path shouldn't take the app down, so it only gets reported
+        let written = tokio::fs::write(&path, csv).await;
+        let mut app = self.app.lock().await;
+        match written {
+            Ok(()) => app
+                .status
+                .info(format!("exported {} tasks to {}", tasks.len(), path)),
+            Err(e) => app.status.error(format!("export failed: {}", e)),
+        };
 
         Ok(())
     }
@@ -124,13 +773,14 @@ impl IOHandler {
     async fn update_category(&mut self, c: Category) -> Result<(), sqlx::Error> {
         self.update_status("updating category".to_string()).await;
 
-        sqlx::query("UPDATE category SET name = $1 WHERE id = $2")
+        sqlx::query("UPDATE category SET name = $1, color = $2 WHERE id = $3")
             .bind(c.name)
+            .bind(c.color)
             .bind(c.id)
             .execute(&self.db_pool)
             .await?;
 
-        self.update_status("update successful".to_string()).await;
+        self.app.lock().await.status.info("update successful");
 
         Ok(())
     }
@@ -145,17 +795,349 @@ impl IOHandler {
                 .await?
                 .get("id");
 
-        let mut app = self.app.lock().await;
-        app.categories.push(Category {
+        let created_category = Category {
             name,
             id: created_category_id,
+            color: None,
+        };
+
+        // Recorded here rather than in the editor since undoing needs the id assigned above
+        let mut app = self.app.lock().await;
+        app.undo_history.record(UndoEntry {
+            description: format!("create category {}", created_category.name),
+            undo: vec![IOEvent::DeleteCategory(created_category_id)],
+            redo: vec![IOEvent::RestoreCategory(created_category.clone())],
         });
+        if !app.categories.iter().any(|c| c.id == created_category_id) {
+            app.categories.push(created_category);
+        }
+        app.status.info("category created");
 
         Ok(())
     }
 
+    // Only removes categories no task is in anymore, tasks added since it was created keep it
+    async fn delete_category(&mut self, id: i32) -> Result<(), sqlx::Error> {
+        self.update_status("deleting category".to_string()).await;
+
+        let deleted = sqlx::query(
+            "DELETE FROM category WHERE id = $1 AND NOT EXISTS (SELECT 1 FROM task WHERE category_id = $1)",
+        )
+        .bind(id)
+        .execute(&self.db_pool)
+        .await?
+        .rows_affected();
+
+        let mut app = self.app.lock().await;
+        if deleted == 0 {
+            app.status.warn("category still has tasks, so it was kept");
+            return Ok(());
+        }
+        app.categories.retain(|c| c.id != id);
+        let last_category = app.categories.len().saturating_sub(1);
+        if app.category_list_state.selected().unwrap_or(0) > last_category {
+            app.category_list_state.select(Some(last_category));
+        }
+        app.status.info("category deleted");
+
+        Ok(())
+    }
+
+    // Re-inserts a deleted category under its old id, unless that id is somehow back in use
+    async fn restore_category(&mut self, c: Category) -> Result<(), sqlx::Error> {
+        self.update_status("restoring category".to_string()).await;
+
+        sqlx::query(
+            "INSERT INTO category (id, name, color) VALUES ($1, $2, $3) ON CONFLICT (id) DO NOTHING",
+        )
+        .bind(c.id)
+        .bind(c.name.clone())
+        .bind(c.color.clone())
+        .execute(&self.db_pool)
+        .await?;
+
+        let mut app = self.app.lock().await;
+        if !app.categories.iter().any(|existing| existing.id == c.id) {
+            app.categories.push(c);
+        }
+        app.status.info("category restored");
+
+        Ok(())
+    }
+
+    // Shows the step in progress without keeping it in the message log
     async fn update_status(&mut self, s: String) {
         let mut app = self.app.lock().await;
-        app.status_text = s;
+        app.status.show(s);
+    }
+}
+
+// Quotes a value if it would otherwise break the csv row
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn category_from_row(r: &PgRow) -> Category {
+    Category {
+        id: r.get("id"),
+        name: r.get("name"),
+        color: r.get("color"),
+    }
+}
+
+// Category of a task row selected along with category_name and category_color
+fn joined_category(r: &PgRow) -> Category {
+    Category {
+        id: r.get("category_id"),
+        name: r.get("category_name"),
+        color: r.get("category_color"),
+    }
+}
+
+fn task_from_row(r: &PgRow, category: Category) -> Task {
+    Task {
+        id: r.get("id"),
+        name: r.get("name"),
+        due_date: r.get("due_date"),
+        scheduled_date: r.get("scheduled_date"),
+        estimate_minutes: r.get("estimate_minutes"),
+        priority: r.get("priority"),
+        tags: r.get("tags"),
+        completed: r.get("completed"),
+        version: r.get("version"),
+        category,
+    }
+}
+
+async fn fetch_task(db_pool: &sqlx::PgPool, id: i32) -> Result<Option<Task>, sqlx::Error> {
+    Ok(sqlx::query(
+        "SELECT t.*, c.name AS category_name, c.color AS category_color FROM task t JOIN category c ON c.id = t.category_id WHERE t.id = $1",
+    )
+    .bind(id)
+    .fetch_optional(db_pool)
+    .await?
+    .map(|r| task_from_row(&r, joined_category(&r))))
+}
+
+// Reads the tasks and holds their rows until the transaction ends, so they can't change in between
+async fn lock_tasks(
+    transaction: &mut Transaction<'_, Postgres>,
+    ids: &[i32],
+) -> Result<Vec<Task>, sqlx::Error> {
+    Ok(sqlx::query(
+        "SELECT t.*, c.name AS category_name, c.color AS category_color FROM task t JOIN category c ON c.id = t.category_id WHERE t.id = ANY($1) FOR UPDATE OF t",
+    )
+    .bind(ids)
+    .fetch_all(&mut **transaction)
+    .await?
+    .iter()
+    .map(|r| task_from_row(r, joined_category(r)))
+    .collect())
+}
+
+// Writes a task_event row for every field that differs, a missing side meaning the task was
+// created or deleted
+async fn log_task_events(
+    transaction: &mut Transaction<'_, Postgres>,
+    task_id: i32,
+    old: Option<&Task>,
+    new: Option<&Task>,
+) -> Result<(), sqlx::Error> {
+    let kind = match (old, new) {
+        (None, _) => "create",
+        (_, None) => "delete",
+        _ => "update",
+    };
+
+    for field in TaskField::ALL {
+        let (old_value, new_value) = (old.map(|t| field.display(t)), new.map(|t| field.display(t)));
+        if old_value == new_value {
+            continue;
+        }
+
+        sqlx::query(
+            "INSERT INTO task_event (task_id, kind, field, old_value, new_value) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(task_id)
+        .bind(kind)
+        .bind(field.label())
+        .bind(old_value)
+        .bind(new_value)
+        .execute(&mut **transaction)
+        .await?;
+    }
+
+    Ok(())
+}
+
+// Applies changes announced by the notify triggers on task and category as they arrive, so edits
+// from other clients show up without waiting for a full refresh
+pub struct ChangeListener {
+    app: Arc<tokio::sync::Mutex<App>>,
+    db_pool: sqlx::PgPool,
+    io_tx: mpsc::Sender<IOEvent>,
+    connected_before: bool,
+    last_error: Option<String>, // reported since the last successful connection
+}
+
+impl ChangeListener {
+    pub fn new(
+        app: Arc<tokio::sync::Mutex<App>>,
+        db_pool: sqlx::PgPool,
+        io_tx: mpsc::Sender<IOEvent>,
+    ) -> Self {
+        Self {
+            app,
+            db_pool,
+            io_tx,
+            connected_before: false,
+            last_error: None,
+        }
+    }
+
+    // Only returns once the connection can't be re-established, call again to retry
+    pub async fn listen(&mut self) -> Result<(), sqlx::Error> {
+        let mut listener = PgListener::connect_with(&self.db_pool).await?;
+        listener.listen("tuitask_change").await?;
+        self.last_error = None;
+
+        if self.connected_before {
+            self.request_refresh().await;
+        }
+        self.connected_before = true;
+
+        loop {
+            match listener.try_recv().await? {
+                Some(notification) => self.apply(notification.payload()).await?,
+                // Connection dropped and was re-established, so notifications may have been missed
+                None => self.request_refresh().await,
+            }
+        }
+    }
+
+    // Logs why listening stopped, once per run of the same error so retries don't flood the log
+    pub async fn report(&mut self, error: sqlx::Error) {
+        let error = error.to_string();
+        if self.last_error.as_ref() != Some(&error) {
+            self.app.lock().await.status.error(format!(
+                "lost connection for live updates, retrying: {}",
+                error
+            ));
+            self.last_error = Some(error);
+        }
+    }
+
+    async fn request_refresh(&self) {
+        if self.io_tx.send(IOEvent::RefreshData).await.is_err() {
+            panic!("database thread not receiving messages");
+        }
+    }
+
+    // Payloads look like "task:UPDATE:12"
+    async fn apply(&self, payload: &str) -> Result<(), sqlx::Error> {
+        let mut parts = payload.splitn(3, ':');
+        let (table, operation, id) = (
+            parts.next().unwrap_or_default(),
+            parts.next().unwrap_or_default(),
+            parts.next().and_then(|id| id.parse::<i32>().ok()),
+        );
+        let Some(id) = id else {
+            return Ok(());
+        };
+
+        match (table, operation) {
+            ("task", "DELETE") => self.apply_task(id, None).await,
+            ("task", _) => {
+                let task = fetch_task(&self.db_pool, id).await?;
+                self.apply_task(id, task).await;
+            }
+            ("category", "DELETE") => self.apply_category(id, None).await,
+            ("category", _) => {
+                let category = sqlx::query("SELECT * FROM category WHERE id = $1")
+                    .bind(id)
+                    .fetch_optional(&self.db_pool)
+                    .await?
+                    .map(|r| category_from_row(&r));
+
+                self.apply_category(id, category).await;
+            }
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    async fn apply_task(&self, id: i32, task: Option<Task>) {
+        let mut app = self.app.lock().await;
+        let selected_task = app.selected_task_id();
+
+        match task {
+            Some(t) => app.task_list.upsert(t),
+            None => {
+                app.task_list.remove(id);
+                if app.timer.as_ref().is_some_and(|t| t.task_id == id) {
+                    app.timer = None;
+                }
+                if app.pomodoro.as_ref().is_some_and(|p| p.task_id == id) {
+                    app.pomodoro = None;
+                }
+
+                // Editors act on the selected task, so don't leave one open on a deleted task
+                if selected_task == Some(id) && app.pop_up.is_some() {
+                    app.close_stale_pop_up("task");
+                }
+            }
+        }
+
+        if let Some(selected_id) = selected_task {
+            app.reselect_task(selected_id);
+        }
+    }
+
+    async fn apply_category(&self, id: i32, category: Option<Category>) {
+        let mut app = self.app.lock().await;
+
+        match category {
+            Some(c) => {
+                app.task_list
+                    .tasks
+                    .iter_mut()
+                    .filter(|t| t.category.id == c.id)
+                    .for_each(|t| t.category = c.clone());
+
+                match app
+                    .categories
+                    .iter_mut()
+                    .find(|existing| existing.id == c.id)
+                {
+                    Some(existing) => *existing = c,
+                    None => app.categories.push(c),
+                }
+            }
+            None => {
+                let selected_index = app.category_list_state.selected().unwrap_or(0);
+                let selected_category = app.categories.get(selected_index).map(|c| c.id);
+                app.categories.retain(|c| c.id != id);
+
+                if selected_category == Some(id) && app.renaming_category() {
+                    app.close_stale_pop_up("category");
+                }
+
+                // Stay on the same category, or its neighbour if it was the one deleted
+                let last_category = app.categories.len().saturating_sub(1);
+                let position = selected_category
+                    .and_then(|s| app.categories.iter().position(|c| c.id == s))
+                    .unwrap_or(selected_index.min(last_category));
+                app.category_list_state.select(Some(position));
+                if let Some(picked) = app.category_edit_state.selected() {
+                    app.category_edit_state
+                        .select(Some(picked.min(last_category)));
+                }
+            }
+        }
     }
 }
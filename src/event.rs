@@ -1,4 +1,5 @@
-use crate::key::Key;
+use crate::{key::Key, mouse::Mouse};
+use crossterm::event::Event;
 use std::{
     sync::{
         atomic::{AtomicBool, Ordering},
@@ -10,6 +11,8 @@ use tokio::sync::mpsc::{self};
 
 pub enum AppEvent {
     Input(Key),
+    Paste(String),
+    Mouse(Mouse),
     Tick,
 }
 pub struct AppEventHandler {
@@ -26,14 +29,15 @@ impl AppEventHandler {
         let event_tx = tx.clone();
         let event_stopped = stopped.clone();
 
-        // Spawns thread to handle keypress events
+        // Spawns thread to handle keypress, paste and mouse events
         tokio::spawn(async move {
             loop {
                 let event = if crossterm::event::poll(tick_rate).unwrap() {
-                    if let crossterm::event::Event::Key(key) = crossterm::event::read().unwrap() {
-                        AppEvent::Input(Key::from(key))
-                    } else {
-                        AppEvent::Tick
+                    match crossterm::event::read().unwrap() {
+                        Event::Key(key) => AppEvent::Input(Key::from(key)),
+                        Event::Paste(text) => AppEvent::Paste(text),
+                        Event::Mouse(mouse) => AppEvent::Mouse(Mouse::from(mouse)),
+                        _ => AppEvent::Tick,
                     }
                 } else {
                     AppEvent::Tick
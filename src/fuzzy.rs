@@ -0,0 +1,26 @@
+// Scores how well query matches candidate as a case-insensitive subsequence, None if it doesn't
+// match at all. Consecutive characters and characters at the start of words score higher.
+pub fn score(query: &str, candidate: &str) -> Option<i64> {
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut total = 0;
+    let mut position = 0;
+    let mut previous_match: Option<usize> = None;
+
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = position + candidate[position..].iter().position(|c| *c == q)?;
+
+        total += 1;
+        if previous_match.is_some_and(|p| p + 1 == found) {
+            total += 5;
+        }
+        if found == 0 || !candidate[found - 1].is_alphanumeric() {
+            total += 3;
+        }
+        total -= (found - position) as i64;
+
+        previous_match = Some(found);
+        position = found + 1;
+    }
+
+    Some(total)
+}
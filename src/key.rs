@@ -1,72 +1,409 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
 
-#[derive(PartialEq)]
+// A key press with its modifiers. Common chords get their own variant so they can be matched
+// directly, everything else is kept whole in Chord.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum Key {
     Number(char),
     Char(char),
     Ctrl(char),
+    Alt(char),
     Enter,
     Esc,
     Tab,
     ShiftTab,
     Backspace,
+    Delete,
+    Insert,
     Left,
     Right,
     Up,
     Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    F(u8),
+    Chord(Modifiers, Code), // e.g. shift-up or ctrl-alt-x
     Unused,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+}
+
+// The key itself, regardless of modifiers
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Code {
+    Char(char),
+    Enter,
+    Esc,
+    Tab,
+    Backspace,
+    Delete,
+    Insert,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    F(u8),
+}
+
+impl Key {
+    // The one Key for a chord, so pressing it always compares equal to how it was bound
+    pub fn new(code: Code, mut modifiers: Modifiers) -> Key {
+        // Shift is already part of the character, e.g. "V" or ">"
+        if let Code::Char(c) = code {
+            if modifiers.shift {
+                modifiers.shift = false;
+                return Key::new(Code::Char(c.to_ascii_uppercase()), modifiers);
+            }
+        }
+
+        let Modifiers { ctrl, alt, shift } = modifiers;
+        match (code, ctrl, alt, shift) {
+            (Code::Char(c), false, false, false) if c.is_ascii_digit() => Key::Number(c),
+            (Code::Char(c), false, false, false) => Key::Char(c),
+            (Code::Char(c), true, false, false) => Key::Ctrl(c),
+            (Code::Char(c), false, true, false) => Key::Alt(c),
+            (Code::Tab, false, false, true) => Key::ShiftTab,
+            (code, false, false, false) => match code {
+                Code::Char(_) => unreachable!(),
+                Code::Enter => Key::Enter,
+                Code::Esc => Key::Esc,
+                Code::Tab => Key::Tab,
+                Code::Backspace => Key::Backspace,
+                Code::Delete => Key::Delete,
+                Code::Insert => Key::Insert,
+                Code::Left => Key::Left,
+                Code::Right => Key::Right,
+                Code::Up => Key::Up,
+                Code::Down => Key::Down,
+                Code::Home => Key::Home,
+                Code::End => Key::End,
+                Code::PageUp => Key::PageUp,
+                Code::PageDown => Key::PageDown,
+                Code::F(n) => Key::F(n),
+            },
+            _ => Key::Chord(modifiers, code),
+        }
+    }
+
+    // Inverse of new, None for Unused
+    pub fn parts(&self) -> Option<(Modifiers, Code)> {
+        let none = Modifiers::default();
+        Some(match *self {
+            Key::Number(c) | Key::Char(c) => (none, Code::Char(c)),
+            Key::Ctrl(c) => (Modifiers { ctrl: true, ..none }, Code::Char(c)),
+            Key::Alt(c) => (Modifiers { alt: true, ..none }, Code::Char(c)),
+            Key::ShiftTab => (
+                Modifiers {
+                    shift: true,
+                    ..none
+                },
+                Code::Tab,
+            ),
+            Key::Chord(modifiers, code) => (modifiers, code),
+            Key::Enter => (none, Code::Enter),
+            Key::Esc => (none, Code::Esc),
+            Key::Tab => (none, Code::Tab),
+            Key::Backspace => (none, Code::Backspace),
+            Key::Delete => (none, Code::Delete),
+            Key::Insert => (none, Code::Insert),
+            Key::Left => (none, Code::Left),
+            Key::Right => (none, Code::Right),
+            Key::Up => (none, Code::Up),
+            Key::Down => (none, Code::Down),
+            Key::Home => (none, Code::Home),
+            Key::End => (none, Code::End),
+            Key::PageUp => (none, Code::PageUp),
+            Key::PageDown => (none, Code::PageDown),
+            Key::F(n) => (none, Code::F(n)),
+            Key::Unused => return None,
+        })
+    }
+}
+
 // Converts crossterm backend key events to custom enum
 impl From<KeyEvent> for Key {
     fn from(value: KeyEvent) -> Self {
-        match value {
-            KeyEvent {
-                code: KeyCode::Char(c),
-                modifiers: KeyModifiers::CONTROL,
-                ..
-            } => Key::Ctrl(c),
-            KeyEvent {
-                code: KeyCode::Char(c),
-                ..
-            } => match c.to_digit(10) {
-                Some(_) => Key::Number(c),
-                None => Key::Char(c),
+        let modifiers = Modifiers {
+            ctrl: value.modifiers.contains(KeyModifiers::CONTROL),
+            alt: value.modifiers.contains(KeyModifiers::ALT),
+            shift: value.modifiers.contains(KeyModifiers::SHIFT),
+        };
+
+        let code = match value.code {
+            KeyCode::Char(c) => Code::Char(c),
+            KeyCode::Enter => Code::Enter,
+            KeyCode::Esc => Code::Esc,
+            KeyCode::Tab => Code::Tab,
+            KeyCode::BackTab => {
+                return Key::new(
+                    Code::Tab,
+                    Modifiers {
+                        shift: true,
+                        ..modifiers
+                    },
+                )
+            }
+            KeyCode::Backspace => Code::Backspace,
+            KeyCode::Delete => Code::Delete,
+            KeyCode::Insert => Code::Insert,
+            KeyCode::Left => Code::Left,
+            KeyCode::Right => Code::Right,
+            KeyCode::Up => Code::Up,
+            KeyCode::Down => Code::Down,
+            KeyCode::Home => Code::Home,
+            KeyCode::End => Code::End,
+            KeyCode::PageUp => Code::PageUp,
+            KeyCode::PageDown => Code::PageDown,
+            KeyCode::F(n) => Code::F(n),
+            _ => return Key::Unused,
+        };
+
+        Key::new(code, modifiers)
+    }
+}
+
+// Names keys the way the hints spell them, e.g. "x", "ctrl-r", "shift-up" or "f5"
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((modifiers, code)) = self.parts() else {
+            return write!(f, "?");
+        };
+
+        if modifiers.ctrl {
+            write!(f, "ctrl-")?;
+        }
+        if modifiers.alt {
+            write!(f, "alt-")?;
+        }
+        if modifiers.shift {
+            write!(f, "shift-")?;
+        }
+
+        match code {
+            Code::Char(' ') => write!(f, "space"),
+            Code::Char(c) => write!(f, "{}", c),
+            Code::Enter => write!(f, "enter"),
+            Code::Esc => write!(f, "esc"),
+            Code::Tab => write!(f, "tab"),
+            Code::Backspace => write!(f, "backspace"),
+            Code::Delete => write!(f, "delete"),
+            Code::Insert => write!(f, "insert"),
+            Code::Left => write!(f, "left"),
+            Code::Right => write!(f, "right"),
+            Code::Up => write!(f, "up"),
+            Code::Down => write!(f, "down"),
+            Code::Home => write!(f, "home"),
+            Code::End => write!(f, "end"),
+            Code::PageUp => write!(f, "pageup"),
+            Code::PageDown => write!(f, "pagedown"),
+            Code::F(n) => write!(f, "f{}", n),
+        }
+    }
+}
+
+// Reads keys written the way they're displayed
+impl FromStr for Key {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = Modifiers::default();
+        let mut rest = s;
+
+        // A lone "-" is a key rather than the start of a modifier
+        while rest.len() > 1 {
+            if let Some(r) = rest.strip_prefix("ctrl-") {
+                modifiers.ctrl = true;
+                rest = r;
+            } else if let Some(r) = rest.strip_prefix("alt-") {
+                modifiers.alt = true;
+                rest = r;
+            } else if let Some(r) = rest.strip_prefix("shift-") {
+                modifiers.shift = true;
+                rest = r;
+            } else {
+                break;
+            }
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => Code::Char(c),
+            _ => match rest {
+                "space" => Code::Char(' '),
+                "enter" => Code::Enter,
+                "esc" => Code::Esc,
+                "tab" => Code::Tab,
+                "backspace" => Code::Backspace,
+                "delete" => Code::Delete,
+                "insert" => Code::Insert,
+                "left" => Code::Left,
+                "right" => Code::Right,
+                "up" => Code::Up,
+                "down" => Code::Down,
+                "home" => Code::Home,
+                "end" => Code::End,
+                "pageup" => Code::PageUp,
+                "pagedown" => Code::PageDown,
+                f => match f.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n) if (1..=24).contains(&n) => Code::F(n),
+                    _ => return Err(format!("unknown key \"{}\"", s)),
+                },
             },
-            KeyEvent {
-                code: KeyCode::Enter,
-                ..
-            } => Key::Enter,
-            KeyEvent {
-                code: KeyCode::BackTab,
-                ..
-            } => Key::ShiftTab,
-            KeyEvent {
-                code: KeyCode::Tab, ..
-            } => Key::Tab,
-            KeyEvent {
-                code: KeyCode::Esc, ..
-            } => Key::Esc,
-            KeyEvent {
-                code: KeyCode::Backspace,
-                ..
-            } => Key::Backspace,
-            KeyEvent {
-                code: KeyCode::Up, ..
-            } => Key::Up,
-            KeyEvent {
-                code: KeyCode::Down,
-                ..
-            } => Key::Down,
-            KeyEvent {
-                code: KeyCode::Left,
-                ..
-            } => Key::Left,
-            KeyEvent {
-                code: KeyCode::Right,
-                ..
-            } => Key::Right,
-            _ => Key::Unused,
+        };
+
+        Ok(Key::new(code, modifiers))
+    }
+}
+
+impl Serialize for Key {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+// Keys pressed one after another to trigger a binding, written like "gg" or "ctrl-w j"
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct KeySequence(pub Vec<Key>);
+
+impl fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keys: Vec<String> = self.0.iter().map(|k| k.to_string()).collect();
+
+        // Runs of plain characters read best without spaces like in vim, unless they'd spell out
+        // the name of another key
+        let concatenated = keys.concat();
+        if keys.len() > 1
+            && keys.iter().all(|k| k.chars().count() == 1)
+            && concatenated.parse::<Key>().is_err()
+        {
+            write!(f, "{}", concatenated)
+        } else {
+            write!(f, "{}", keys.join(" "))
+        }
+    }
+}
+
+impl FromStr for KeySequence {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut keys = Vec::new();
+        for word in s.split_whitespace() {
+            match word.parse::<Key>() {
+                Ok(key) => keys.push(key),
+                // "gg" is two presses of g, since it isn't the name of a key
+                Err(e) if word.contains('-') => return Err(e),
+                Err(_) => keys.extend(
+                    word.chars()
+                        .map(|c| Key::new(Code::Char(c), Modifiers::default())),
+                ),
+            }
+        }
+
+        if keys.is_empty() {
+            return Err("empty key sequence".to_string());
+        }
+        Ok(KeySequence(keys))
+    }
+}
+
+impl Serialize for KeySequence {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for KeySequence {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sequence(s: &str) -> KeySequence {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn keys_round_trip() {
+        for name in [
+            "x",
+            "V",
+            ">",
+            "-",
+            "7",
+            "space",
+            "ctrl-r",
+            "alt-j",
+            "shift-up",
+            "shift-tab",
+            "ctrl-alt-x",
+            "f5",
+            "f24",
+            "pagedown",
+        ] {
+            assert_eq!(name.parse::<Key>().unwrap().to_string(), name);
+        }
+    }
+
+    #[test]
+    fn shift_is_folded_into_characters() {
+        assert_eq!("shift-v".parse::<Key>(), Ok(Key::Char('V')));
+        assert_eq!("shift-tab".parse::<Key>(), Ok(Key::ShiftTab));
+        assert_eq!("7".parse::<Key>(), Ok(Key::Number('7')));
+    }
+
+    #[test]
+    fn unknown_keys() {
+        assert!("f0".parse::<Key>().is_err());
+        assert!("f25".parse::<Key>().is_err());
+        assert!("f99999999999".parse::<Key>().is_err());
+        assert!("hyper-x".parse::<Key>().is_err());
+        assert!("ctrl-".parse::<Key>().is_err());
+    }
+
+    #[test]
+    fn sequences_round_trip() {
+        for written in ["gg", "dd", "ctrl-w j", "g space", "f 1", "e n d", "f5"] {
+            assert_eq!(sequence(written).to_string(), written);
         }
     }
+
+    #[test]
+    fn sequences_split_runs_of_characters() {
+        assert_eq!(sequence("gt").0, vec![Key::Char('g'), Key::Char('t')]);
+        assert_eq!(sequence("end").0, vec![Key::End]);
+        assert_eq!(
+            sequence("ctrl-w  J").0,
+            vec![Key::Ctrl('w'), Key::Char('J')]
+        );
+        assert!("".parse::<KeySequence>().is_err());
+        assert!("g ctrl-nope".parse::<KeySequence>().is_err());
+    }
 }
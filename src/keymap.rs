@@ -0,0 +1,178 @@
+use crate::{
+    action::{Action, Context, DEFAULT_BINDINGS},
+    config::config_dir,
+    key::{Key, KeySequence},
+};
+use std::{collections::HashMap, error, fs};
+
+// Key bindings per context, the defaults with any from keymap.toml layered over them
+pub struct Keymap {
+    bindings: Vec<(Context, KeySequence, Action)>,
+}
+
+// An action with every key sequence that triggers it
+pub type Binding = (Action, Vec<KeySequence>);
+
+// How far the keys pressed so far get towards a binding
+pub enum KeyMatch {
+    None,
+    Full(Action),
+    Partial(Option<Action>), // longer bindings start with the keys, which may trigger an action themselves
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap {
+            bindings: DEFAULT_BINDINGS
+                .iter()
+                .map(|(context, keys, action)| (*context, KeySequence(keys.to_vec()), *action))
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    // Reads keymap.toml from the config directory, refusing to start if any binding in it is
+    // invalid or conflicts with another
+    pub fn load() -> Result<Self, Box<dyn error::Error>> {
+        let mut keymap = Keymap::default();
+
+        if let Some(path) = config_dir()
+            .map(|d| d.join("keymap.toml"))
+            .filter(|p| p.exists())
+        {
+            let sections: HashMap<String, HashMap<KeySequence, String>> =
+                toml::from_str(&fs::read_to_string(path)?)?;
+            for (section, bindings) in sections {
+                let context = Context::from_id(&section)
+                    .ok_or(format!("keymap.toml: unknown section [{}]", section))?;
+                for (key, action) in bindings {
+                    keymap.bind(context, key, &action)?;
+                }
+            }
+        }
+
+        keymap.validate()?;
+        Ok(keymap)
+    }
+
+    // Replaces whatever key did in the context, "none" just unbinds it
+    fn bind(&mut self, context: Context, key: KeySequence, action: &str) -> Result<(), String> {
+        self.bindings
+            .retain(|(c, k, _)| !(*c == context && *k == key));
+
+        if action != "none" {
+            let action = Action::from_id(action).ok_or(format!(
+                "keymap.toml: unknown action \"{}\" for {} in [{}]",
+                action,
+                key,
+                context.id()
+            ))?;
+            self.bindings.push((context, key, action));
+        }
+
+        Ok(())
+    }
+
+    // View bindings are looked up before global ones and a pop up's before those shared by every
+    // pop up, so a key bound in both would hide one of them
+    fn validate(&self) -> Result<(), String> {
+        for (context, key, action) in &self.bindings {
+            let Some(fallback) = context.fallback() else {
+                continue;
+            };
+            if let Some((_, _, other)) = self
+                .bindings
+                .iter()
+                .find(|(c, k, a)| *c == fallback && k == key && a != action)
+            {
+                return Err(format!(
+                    "keymap conflict: {} is bound to {} in [{}] and {} in [{}]",
+                    key,
+                    other.id(),
+                    fallback.id(),
+                    action.id(),
+                    context.id()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    // The first binding for a single key press in the contexts, which are given in order of
+    // precedence
+    pub fn action_for(&self, key: &Key, contexts: &[Context]) -> Option<Action> {
+        self.exact_match(&[*key], contexts)
+    }
+
+    // Whether keys pressed one after another trigger an action, or could once more are pressed
+    pub fn resolve(&self, keys: &[Key], contexts: &[Context]) -> KeyMatch {
+        let longer = self.bindings.iter().any(|(c, sequence, _)| {
+            contexts.contains(c) && sequence.0.len() > keys.len() && sequence.0.starts_with(keys)
+        });
+
+        match (self.exact_match(keys, contexts), longer) {
+            (action, true) => KeyMatch::Partial(action),
+            (Some(action), false) => KeyMatch::Full(action),
+            (None, false) => KeyMatch::None,
+        }
+    }
+
+    fn exact_match(&self, keys: &[Key], contexts: &[Context]) -> Option<Action> {
+        contexts.iter().find_map(|context| {
+            self.bindings
+                .iter()
+                .find(|(c, sequence, _)| c == context && sequence.0 == keys)
+                .map(|(_, _, action)| *action)
+        })
+    }
+
+    // Every action reachable in the contexts with the keys that trigger it, skipping keys that
+    // are shadowed by a context with higher precedence
+    pub fn available_actions(&self, contexts: &[Context]) -> Vec<Binding> {
+        let mut actions: Vec<Binding> = Vec::new();
+
+        for context in contexts {
+            for (_, sequence, action) in self.bindings.iter().filter(|(c, _, _)| c == context) {
+                if self.exact_match(&sequence.0, contexts) != Some(*action) {
+                    continue;
+                }
+
+                match actions.iter_mut().find(|(a, _)| a == action) {
+                    Some((_, sequences)) => sequences.push(sequence.clone()),
+                    None => actions.push((*action, vec![sequence.clone()])),
+                }
+            }
+        }
+
+        actions
+    }
+
+    // Footer hints for the contexts, e.g. "Edit[e]  Complete[enter]"
+    pub fn hints(&self, contexts: &[Context]) -> String {
+        format_hints(&self.available_actions(contexts))
+    }
+
+    // Hints for just the given actions in that order, for the line at the bottom of a pop up.
+    // Actions without a key are left out.
+    pub fn hints_for(&self, contexts: &[Context], actions: &[Action]) -> String {
+        let available = self.available_actions(contexts);
+        let bindings: Vec<Binding> = actions
+            .iter()
+            .filter_map(|action| available.iter().find(|(a, _)| a == action).cloned())
+            .collect();
+        format_hints(&bindings)
+    }
+}
+
+fn format_hints(bindings: &[Binding]) -> String {
+    bindings
+        .iter()
+        .map(|(action, keys)| {
+            let keys: Vec<String> = keys.iter().map(|k| k.to_string()).collect();
+            format!("{}[{}]", action.hint(), keys.join("/"))
+        })
+        .collect::<Vec<String>>()
+        .join("  ")
+}
@@ -1,14 +1,29 @@
+mod action;
 mod app;
 mod category;
+mod clipboard;
+mod command;
+mod config;
 mod database;
 mod event;
+mod fuzzy;
 mod key;
+mod keymap;
+mod mouse;
+mod quick_add;
+mod status;
 mod task;
+mod theme;
+mod tracking;
 mod ui;
+mod undo;
 
 use app::{App, AppReturn};
-use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen};
-use database::{IOEvent, IOHandler};
+use crossterm::{
+    event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste},
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen},
+};
+use database::{ChangeListener, IOEvent, IOHandler};
 use event::{AppEvent, AppEventHandler};
 use ratatui::{prelude::CrosstermBackend, Terminal};
 use std::{error, io::stdout, sync::Arc, time::Duration};
@@ -21,13 +36,33 @@ async fn main() -> Result<(), Box<dyn error::Error>> {
     let pool = sqlx::PgPool::connect(url).await?;
     sqlx::migrate!("./migrations").run(&pool).await?;
 
+    let config = config::Config::load()?;
+    let keymap = keymap::Keymap::load()?;
+    let theme = theme::Theme::load(&config)?;
+
     // Set up channel to database handler
     let (io_tx, mut io_rx) = mpsc::channel::<IOEvent>(100);
 
     // Create app and wrap in Mutex/Arc to allow IO/UI to both mutate data
-    let app = Arc::new(tokio::sync::Mutex::new(app::App::new(io_tx.clone())));
+    let app = Arc::new(tokio::sync::Mutex::new(app::App::new(
+        io_tx.clone(),
+        config,
+        keymap,
+        theme,
+    )));
     let app_ui = Arc::clone(&app);
 
+    // Spawn listener applying changes made by other clients, retrying whenever the connection drops
+    let mut listener = ChangeListener::new(Arc::clone(&app), pool.clone(), io_tx.clone());
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = listener.listen().await {
+                listener.report(e).await;
+            }
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+    });
+
     // Spawn database handler task
     tokio::spawn(async move {
         let mut handler = IOHandler::new(app, pool);
@@ -45,10 +80,10 @@ async fn main() -> Result<(), Box<dyn error::Error>> {
 async fn start_ui(app: Arc<tokio::sync::Mutex<App>>) -> Result<(), Box<dyn error::Error>> {
     // Set up terminal window
     crossterm::terminal::enable_raw_mode()?;
-    crossterm::execute!(stdout(), EnterAlternateScreen)?;
+    crossterm::execute!(stdout(), EnterAlternateScreen, EnableBracketedPaste)?;
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
 
-    // Initialize app
+    // Initialize app, which also turns on mouse capture unless it's disabled in the config
     app.lock().await.initialize().await;
 
     // Event loop (waiting for keypresses/automatically ticking)
@@ -59,6 +94,8 @@ async fn start_ui(app: Arc<tokio::sync::Mutex<App>>) -> Result<(), Box<dyn error
 
         let result = match app_event_handler.next().await {
             AppEvent::Input(key) => app.do_action(key).await,
+            AppEvent::Paste(text) => app.paste(&text),
+            AppEvent::Mouse(mouse) => app.do_mouse_action(mouse).await,
             AppEvent::Tick => app.update_on_tick().await,
         };
 
@@ -69,7 +106,12 @@ async fn start_ui(app: Arc<tokio::sync::Mutex<App>>) -> Result<(), Box<dyn error
     }
 
     // Exit terminal screen
-    crossterm::execute!(stdout(), LeaveAlternateScreen)?;
+    crossterm::execute!(
+        stdout(),
+        DisableMouseCapture,
+        DisableBracketedPaste,
+        LeaveAlternateScreen
+    )?;
     crossterm::terminal::disable_raw_mode()?;
 
     Ok(())
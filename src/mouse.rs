@@ -0,0 +1,26 @@
+use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+// The mouse input the app reacts to, at a column and row of the screen
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mouse {
+    Click(u16, u16, bool), // true while ctrl is held
+    ScrollUp,
+    ScrollDown,
+    Unused,
+}
+
+// Converts crossterm backend mouse events to custom enum
+impl From<MouseEvent> for Mouse {
+    fn from(value: MouseEvent) -> Self {
+        match value.kind {
+            MouseEventKind::Down(MouseButton::Left) => Mouse::Click(
+                value.column,
+                value.row,
+                value.modifiers.contains(KeyModifiers::CONTROL),
+            ),
+            MouseEventKind::ScrollUp => Mouse::ScrollUp,
+            MouseEventKind::ScrollDown => Mouse::ScrollDown,
+            _ => Mouse::Unused,
+        }
+    }
+}
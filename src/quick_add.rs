@@ -0,0 +1,183 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+
+use crate::{
+    category::Category,
+    fuzzy,
+    task::{add_days, parse_minutes, parse_priority},
+};
+
+// Fields read from a quick-add line such as "Read ch 4 @Physics fri !high #reading ~45m".
+// Words without a marker make up the name, tokens that don't parse are kept in errors.
+#[derive(Default)]
+pub struct QuickAdd {
+    pub name: String,
+    pub category: Option<Category>,
+    pub date: Option<NaiveDate>,
+    pub priority: i16,
+    pub tags: Vec<String>,
+    pub estimate_minutes: Option<i32>,
+    pub errors: Vec<String>,
+}
+
+pub fn parse(input: &str, categories: &[Category], today: NaiveDate) -> QuickAdd {
+    let mut parsed = QuickAdd::default();
+    let mut name_words = Vec::new();
+
+    for word in input.split_whitespace() {
+        if let Some(query) = word.strip_prefix('@') {
+            match best_category(query, categories) {
+                Some(c) => parsed.category = Some(c.clone()),
+                None => parsed.errors.push(word.to_string()),
+            }
+        } else if let Some(priority) = word.strip_prefix('!') {
+            match parse_priority(priority) {
+                Some(p) => parsed.priority = p,
+                None => parsed.errors.push(word.to_string()),
+            }
+        } else if let Some(tag) = word.strip_prefix('#').filter(|t| !t.is_empty()) {
+            parsed.tags.push(tag.to_string());
+        } else if let Some(estimate) = word.strip_prefix('~') {
+            match parse_minutes(estimate) {
+                Some(m) => parsed.estimate_minutes = Some(m),
+                None => parsed.errors.push(word.to_string()),
+            }
+        } else if let Some(date) = parse_relative_date(word, today) {
+            parsed.date = Some(date);
+        } else if is_offset(word) {
+            parsed.errors.push(word.to_string());
+        } else {
+            name_words.push(word);
+        }
+    }
+
+    parsed.name = name_words.join(" ");
+    parsed
+}
+
+fn best_category<'a>(query: &str, categories: &'a [Category]) -> Option<&'a Category> {
+    categories
+        .iter()
+        .filter_map(|c| fuzzy::score(query, &c.name).map(|score| (score, c)))
+        .max_by_key(|(score, _)| *score)
+        .map(|(_, c)| c)
+}
+
+// Looks like a "+3d" offset, even one too far out to land on a date
+fn is_offset(word: &str) -> bool {
+    let digits = word.strip_prefix('+').unwrap_or_default();
+    let digits = digits.strip_suffix(['d', 'w']).unwrap_or(digits);
+    !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+// Understands today/tomorrow, weekday names (the next one, today included), offsets like "+3d"
+// or "+2w", and dates written as 2024-10-31 or 10/31
+pub fn parse_relative_date(word: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let word = word.to_lowercase();
+
+    match word.as_str() {
+        "today" | "tod" => return Some(today),
+        "tomorrow" | "tom" => return add_days(today, 1),
+        _ => (),
+    }
+
+    if let Ok(weekday) = word.parse::<Weekday>() {
+        let days_ahead =
+            (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+        return add_days(today, days_ahead as i64);
+    }
+
+    if let Some(offset) = word.strip_prefix('+') {
+        let (amount, days_per) = match offset.strip_suffix('w') {
+            Some(weeks) => (weeks, 7),
+            None => (offset.strip_suffix('d').unwrap_or(offset), 1),
+        };
+        // Digits only, parse would also take a second sign as in "+-3d"
+        if !amount.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        return add_days(today, amount.parse::<i64>().ok()?.checked_mul(days_per)?);
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(&word, "%Y-%m-%d") {
+        return Some(date);
+    }
+
+    let (month, day) = word.split_once('/')?;
+    NaiveDate::from_ymd_opt(today.year(), month.parse().ok()?, day.parse().ok()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        // A Wednesday
+        NaiveDate::from_ymd_opt(2024, 10, 16).unwrap()
+    }
+
+    fn categories() -> Vec<Category> {
+        ["Physics", "Math"]
+            .iter()
+            .enumerate()
+            .map(|(id, name)| Category {
+                name: name.to_string(),
+                id: id as i32,
+                color: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reads_every_marker() {
+        let parsed = parse(
+            "Read ch 4 @phys fri !high #reading ~1h30",
+            &categories(),
+            today(),
+        );
+
+        assert_eq!(parsed.name, "Read ch 4");
+        assert_eq!(parsed.category.map(|c| c.name), Some("Physics".to_string()));
+        assert_eq!(parsed.date, NaiveDate::from_ymd_opt(2024, 10, 18));
+        assert_eq!(parsed.priority, 3);
+        assert_eq!(parsed.tags, vec!["reading"]);
+        assert_eq!(parsed.estimate_minutes, Some(90));
+        assert!(parsed.errors.is_empty());
+    }
+
+    #[test]
+    fn keeps_unreadable_tokens_as_errors() {
+        let parsed = parse("Essay @nothing !urgent ~0 ~-5m", &categories(), today());
+
+        assert_eq!(parsed.name, "Essay");
+        assert!(parsed.category.is_none());
+        assert_eq!(parsed.errors, vec!["@nothing", "!urgent", "~0", "~-5m"]);
+    }
+
+    #[test]
+    fn offsets_past_the_calendar_are_errors() {
+        let parsed = parse("Lab +99999999 +9999999999999w", &categories(), today());
+
+        assert_eq!(parsed.name, "Lab");
+        assert!(parsed.date.is_none());
+        assert_eq!(parsed.errors, vec!["+99999999", "+9999999999999w"]);
+    }
+
+    #[test]
+    fn relative_dates() {
+        let date = |word| parse_relative_date(word, today());
+
+        assert_eq!(date("today"), Some(today()));
+        assert_eq!(date("TOM"), NaiveDate::from_ymd_opt(2024, 10, 17));
+        assert_eq!(date("wed"), Some(today()));
+        assert_eq!(date("tue"), NaiveDate::from_ymd_opt(2024, 10, 22));
+        assert_eq!(date("+3"), NaiveDate::from_ymd_opt(2024, 10, 19));
+        assert_eq!(date("+2w"), NaiveDate::from_ymd_opt(2024, 10, 30));
+        assert_eq!(date("2025-01-31"), NaiveDate::from_ymd_opt(2025, 1, 31));
+        assert_eq!(date("12/25"), NaiveDate::from_ymd_opt(2024, 12, 25));
+        assert_eq!(date("2/30"), None);
+        assert_eq!(date("+-3d"), None);
+        assert_eq!(date("++3d"), None);
+        assert_eq!(date("+d"), None);
+        assert_eq!(date("+9223372036854775807w"), None);
+    }
+}
@@ -0,0 +1,177 @@
+use chrono::{DateTime, Local};
+use std::time::{Duration, Instant};
+
+// Old messages are dropped past this so a long session doesn't grow without end
+const MAX_MESSAGES: usize = 500;
+// Toasts stacked over the main area at once, the newest ones win
+const MAX_TOASTS: usize = 3;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+pub struct Message {
+    pub at: DateTime<Local>,
+    pub shown_at: Instant, // when it came in, for expiring its toast
+    pub severity: Severity,
+    pub text: String,
+}
+
+// Messages for the session, oldest first, along with a passing one like the IO step in progress,
+// which only shows in the status bar and isn't kept
+#[derive(Default)]
+pub struct StatusLog {
+    messages: Vec<Message>,
+    passing: Option<String>,
+    toasts_from: usize, // messages before this were already seen and get no toast
+}
+
+impl StatusLog {
+    pub fn info(&mut self, text: impl Into<String>) {
+        self.push(Severity::Info, text.into());
+    }
+
+    pub fn warn(&mut self, text: impl Into<String>) {
+        self.push(Severity::Warning, text.into());
+    }
+
+    pub fn error(&mut self, text: impl Into<String>) {
+        self.push(Severity::Error, text.into());
+    }
+
+    fn push(&mut self, severity: Severity, text: String) {
+        self.passing = None;
+        if self.messages.len() == MAX_MESSAGES {
+            self.messages.remove(0);
+            self.toasts_from = self.toasts_from.saturating_sub(1);
+        }
+        self.messages.push(Message {
+            at: Local::now(),
+            shown_at: Instant::now(),
+            severity,
+            text,
+        });
+    }
+
+    pub fn show(&mut self, text: impl Into<String>) {
+        self.passing = Some(text.into());
+    }
+
+    pub fn messages(&self) -> &[Message] {
+        &self.messages
+    }
+
+    // The passing message, or else the last one kept
+    pub fn current(&self) -> &str {
+        match (&self.passing, self.messages.last()) {
+            (Some(passing), _) => passing,
+            (None, Some(message)) => &message.text,
+            (None, None) => "",
+        }
+    }
+
+    // Messages younger than the toast duration, oldest first
+    pub fn toasts(&self, duration: Duration) -> &[Message] {
+        let young = self
+            .messages
+            .iter()
+            .rev()
+            .take(MAX_TOASTS.min(self.messages.len() - self.toasts_from))
+            .take_while(|m| m.shown_at.elapsed() < duration)
+            .count();
+        &self.messages[self.messages.len() - young..]
+    }
+
+    pub fn dismiss_toasts(&mut self) {
+        self.toasts_from = self.messages.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LONG: Duration = Duration::from_secs(60);
+
+    fn texts(messages: &[Message]) -> Vec<&str> {
+        messages.iter().map(|m| m.text.as_str()).collect()
+    }
+
+    #[test]
+    fn keeps_the_newest_toasts() {
+        let mut status = StatusLog::default();
+        assert!(status.toasts(LONG).is_empty());
+
+        for text in ["a", "b", "c", "d"] {
+            status.info(text);
+        }
+        assert_eq!(texts(status.toasts(LONG)), vec!["b", "c", "d"]);
+        assert!(status.toasts(Duration::ZERO).is_empty());
+    }
+
+    #[test]
+    fn toasts_expire() {
+        let mut status = StatusLog::default();
+        status.warn("old");
+        status.error("new");
+        status.messages[0].shown_at -= Duration::from_secs(10);
+
+        assert_eq!(texts(status.toasts(Duration::from_secs(5))), vec!["new"]);
+        // An expired toast hides the older ones too, even ones that are still young
+        status.messages[0].shown_at += Duration::from_secs(10);
+        status.messages[1].shown_at -= Duration::from_secs(10);
+        assert!(status.toasts(Duration::from_secs(5)).is_empty());
+    }
+
+    #[test]
+    fn dismissed_toasts_stay_in_the_log() {
+        let mut status = StatusLog::default();
+        status.info("a");
+        status.info("b");
+        status.dismiss_toasts();
+
+        assert!(status.toasts(LONG).is_empty());
+        status.info("c");
+        assert_eq!(texts(status.toasts(LONG)), vec!["c"]);
+        assert_eq!(texts(status.messages()), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn drops_the_oldest_messages() {
+        let mut status = StatusLog::default();
+        for i in 0..MAX_MESSAGES {
+            status.info(i.to_string());
+        }
+        status.dismiss_toasts();
+        status.info("last");
+
+        assert_eq!(status.messages().len(), MAX_MESSAGES);
+        assert_eq!(status.messages()[0].text, "1");
+        assert_eq!(texts(status.toasts(LONG)), vec!["last"]);
+    }
+
+    #[test]
+    fn passing_messages_are_not_kept() {
+        let mut status = StatusLog::default();
+        status.info("data loaded");
+        status.show("saving...");
+
+        assert_eq!(status.current(), "saving...");
+        assert_eq!(status.messages().len(), 1);
+        status.error("save failed");
+        assert_eq!(status.current(), "save failed");
+    }
+}
@@ -1,6 +1,7 @@
-use chrono::{offset::Local, Days, NaiveDate};
+use chrono::{offset::Local, DateTime, Days, NaiveDate, Utc};
+use std::cmp::Ordering;
 
-use crate::category::Category;
+use crate::{category::Category, fuzzy};
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Task {
@@ -9,39 +10,395 @@ pub struct Task {
     pub completed: bool,
     pub id: i32,
     pub category: Category,
+    pub scheduled_date: Option<NaiveDate>,
+    pub estimate_minutes: Option<i32>,
+    pub priority: i16, // 0 for none, up to 3 for high
+    pub tags: Vec<String>,
+    pub version: i32, // bumped on every update, stale versions are rejected as conflicts
+}
+
+impl Task {
+    // Day the task is planned to be worked on, which is its deadline unless scheduled separately
+    pub fn planned_date(&self) -> NaiveDate {
+        self.scheduled_date.unwrap_or(self.due_date)
+    }
+
+    // Moves the scheduled date if there is one, otherwise the deadline itself
+    pub fn set_planned_date(&mut self, d: NaiveDate) {
+        match self.scheduled_date {
+            Some(_) => self.scheduled_date = Some(d),
+            None => self.due_date = d,
+        }
+    }
+
+    // Number of days past the planned date (zero if not overdue)
+    pub fn days_late(&self, today: NaiveDate) -> i64 {
+        today
+            .signed_duration_since(self.planned_date())
+            .num_days()
+            .max(0)
+    }
+
+    pub fn scheduled_after_due(&self) -> bool {
+        self.scheduled_date.is_some_and(|d| d > self.due_date)
+    }
+
+    // Every word has to match: "@x" the category, "#x" a tag, "!x" the priority, otherwise the name
+    pub fn matches_filter(&self, filter: &str) -> bool {
+        filter.split_whitespace().all(|word| {
+            let word = word.to_lowercase();
+            if let Some(category) = word.strip_prefix('@') {
+                self.category.name.to_lowercase().contains(category)
+            } else if let Some(tag) = word.strip_prefix('#') {
+                self.tags.iter().any(|t| t.to_lowercase() == tag)
+            } else if let Some(priority) = word.strip_prefix('!') {
+                parse_priority(priority) == Some(self.priority)
+            } else {
+                self.name.to_lowercase().contains(&word)
+            }
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TaskField {
+    Name,
+    DueDate,
+    ScheduledDate,
+    Estimate,
+    Priority,
+    Tags,
+    Category,
+    Completed,
+}
+
+impl TaskField {
+    pub const ALL: [TaskField; 8] = [
+        TaskField::Name,
+        TaskField::DueDate,
+        TaskField::ScheduledDate,
+        TaskField::Estimate,
+        TaskField::Priority,
+        TaskField::Tags,
+        TaskField::Category,
+        TaskField::Completed,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TaskField::Name => "Name",
+            TaskField::DueDate => "Due",
+            TaskField::ScheduledDate => "Scheduled",
+            TaskField::Estimate => "Estimate",
+            TaskField::Priority => "Priority",
+            TaskField::Tags => "Tags",
+            TaskField::Category => "Category",
+            TaskField::Completed => "Completed",
+        }
+    }
+
+    pub fn display(&self, t: &Task) -> String {
+        match self {
+            TaskField::Name => t.name.clone(),
+            TaskField::DueDate => t.due_date.format("%F").to_string(),
+            TaskField::ScheduledDate => t
+                .scheduled_date
+                .map_or("None".to_string(), |d| d.format("%F").to_string()),
+            TaskField::Estimate => t
+                .estimate_minutes
+                .map_or("None".to_string(), format_minutes),
+            TaskField::Priority => format_priority(t.priority).to_string(),
+            TaskField::Tags => match t.tags.is_empty() {
+                true => "None".to_string(),
+                false => format_tags(&t.tags),
+            },
+            TaskField::Category => t.category.name.clone(),
+            TaskField::Completed => t.completed.to_string(),
+        }
+    }
+
+    fn copy(&self, from: &Task, to: &mut Task) {
+        match self {
+            TaskField::Name => to.name = from.name.clone(),
+            TaskField::DueDate => to.due_date = from.due_date,
+            TaskField::ScheduledDate => to.scheduled_date = from.scheduled_date,
+            TaskField::Estimate => to.estimate_minutes = from.estimate_minutes,
+            TaskField::Priority => to.priority = from.priority,
+            TaskField::Tags => to.tags = from.tags.clone(),
+            TaskField::Category => to.category = from.category.clone(),
+            TaskField::Completed => to.completed = from.completed,
+        }
+    }
+}
+
+// Local edit rejected because another client changed the task first
+pub struct Conflict {
+    pub mine: Task,
+    pub theirs: Task,
+    pub take_theirs: [bool; TaskField::ALL.len()], // per TaskField::ALL entry, used when merging
+}
+
+impl Conflict {
+    pub fn new(mine: Task, theirs: Task) -> Self {
+        Conflict {
+            mine,
+            theirs,
+            take_theirs: [false; TaskField::ALL.len()],
+        }
+    }
+
+    pub fn differs(&self, field: TaskField) -> bool {
+        field.display(&self.mine) != field.display(&self.theirs)
+    }
+
+    // Mine with the chosen fields replaced by theirs, based on their version so it can be saved
+    pub fn merged(&self) -> Task {
+        let mut merged = Task {
+            version: self.theirs.version,
+            ..self.mine.clone()
+        };
+        for (field, take_theirs) in TaskField::ALL.iter().zip(self.take_theirs) {
+            if take_theirs {
+                field.copy(&self.theirs, &mut merged);
+            }
+        }
+        merged
+    }
+}
+
+// One changed field from the task_event log
+pub struct TaskEvent {
+    pub occurred_at: DateTime<Utc>,
+    pub kind: String,
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+impl TaskEvent {
+    pub fn description(&self) -> String {
+        match (&self.old_value, &self.new_value) {
+            (Some(old), Some(new)) => format!("{}: {} -> {}", self.field, old, new),
+            (Some(value), None) | (None, Some(value)) => format!("{}: {}", self.field, value),
+            (None, None) => self.field.clone(),
+        }
+    }
+}
+
+// When a task was created, last changed and completed, going by its task_event log. Tasks from
+// before the log have no creation time
+pub struct TaskTimestamps {
+    pub task_id: i32,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Clone, PartialEq)]
 pub enum TaskDate {
     Task(Task),
     Date(NaiveDate),
+    Overdue(usize), // header preceding all tasks planned before today, holds their count
 }
 
 pub struct TaskList {
     pub tasks: Vec<Task>,
-    pub current_taskdate: TaskDate, // updates when UI renders task list in some format
+    pub dependencies: Vec<(i32, i32)>, // (blocker id, blocked id) pairs
+    pub current_taskdate: TaskDate,    // updates when UI renders task list in some format
     pub selected_index: usize, // same as above, facilities editing/removing currently selected task
+    pub filter: Option<String>, // hides tasks that don't match, see Task::matches_filter
 }
 
 impl TaskList {
     pub fn new() -> Self {
         TaskList {
             tasks: Vec::new(),
+            dependencies: Vec::new(),
             current_taskdate: TaskDate::Date(NaiveDate::from_ymd_opt(1, 1, 1).unwrap()),
             selected_index: 0,
+            filter: None,
+        }
+    }
+
+    pub fn is_visible(&self, task: &Task) -> bool {
+        self.filter.as_ref().is_none_or(|f| task.matches_filter(f))
+    }
+
+    // Inserts or replaces a task by id. Completions only touch tasks that are still in the list so
+    // they show as crossed out until the next purge, like local completions.
+    pub fn upsert(&mut self, task: Task) {
+        match self.tasks.iter_mut().find(|t| t.id == task.id) {
+            Some(existing) => *existing = task,
+            None if !task.completed => self.tasks.push(task),
+            None => (),
+        }
+    }
+
+    pub fn remove(&mut self, id: i32) {
+        self.tasks.retain(|t| t.id != id);
+        self.dependencies
+            .retain(|(blocker, blocked)| *blocker != id && *blocked != id);
+    }
+
+    pub fn blockers_of(&self, id: i32) -> Vec<i32> {
+        self.dependencies
+            .iter()
+            .filter(|(_, blocked)| *blocked == id)
+            .map(|(blocker, _)| *blocker)
+            .collect()
+    }
+
+    // Only incomplete tasks are kept loaded, so a blocker that isn't in the list is done
+    pub fn is_blocked(&self, id: i32) -> bool {
+        self.blockers_of(id)
+            .iter()
+            .any(|blocker| self.tasks.iter().any(|t| t.id == *blocker && !t.completed))
+    }
+
+    // Adding blocker -> blocked closes a cycle if blocked already (transitively) blocks blocker
+    pub fn creates_cycle(&self, blocker: i32, blocked: i32) -> bool {
+        let mut to_visit = vec![blocked];
+        let mut visited = Vec::new();
+
+        while let Some(id) = to_visit.pop() {
+            if id == blocker {
+                return true;
+            }
+            if !visited.contains(&id) {
+                visited.push(id);
+                to_visit.extend(
+                    self.dependencies
+                        .iter()
+                        .filter(|(from, _)| *from == id)
+                        .map(|(_, to)| *to),
+                );
+            }
+        }
+
+        false
+    }
+
+    // Tasks other than exclude_id whose names fuzzy match query, best match first
+    pub fn fuzzy_search(&self, query: &str, exclude_id: i32) -> Vec<&Task> {
+        let mut matches: Vec<(i64, &Task)> = self
+            .tasks
+            .iter()
+            .filter(|t| t.id != exclude_id && !t.completed)
+            .filter_map(|t| fuzzy::score(query, &t.name).map(|score| (score, t)))
+            .collect();
+        matches.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+
+        matches.into_iter().map(|(_, t)| t).collect()
+    }
+
+    // Total estimated minutes of incomplete tasks planned for the given day
+    pub fn planned_minutes(&self, d: NaiveDate) -> i32 {
+        self.tasks
+            .iter()
+            .filter(|t| !t.completed && t.planned_date() == d)
+            .filter_map(|t| t.estimate_minutes)
+            .sum()
+    }
+
+    pub fn overdue_minutes(&self) -> i32 {
+        let today = Local::now().date_naive();
+        self.tasks
+            .iter()
+            .filter(|t| !t.completed && t.planned_date() < today)
+            .filter_map(|t| t.estimate_minutes)
+            .sum()
+    }
+
+    pub fn overdue_count(&self) -> usize {
+        self.overdue_ids().len()
+    }
+
+    // Ids of the tasks from a to b inclusive, in the order of the last generated upcoming list
+    pub fn ids_between(&self, a: i32, b: i32) -> Vec<i32> {
+        let position = |id: i32| self.tasks.iter().position(|t| t.id == id);
+        match (position(a), position(b)) {
+            (Some(a), Some(b)) => self.tasks[a.min(b)..=a.max(b)]
+                .iter()
+                .filter(|t| self.is_visible(t))
+                .map(|t| t.id)
+                .collect(),
+            _ => Vec::new(),
         }
     }
 
+    pub fn overdue_ids(&self) -> Vec<i32> {
+        let today = Local::now().date_naive();
+        self.tasks
+            .iter()
+            .filter(|t| !t.completed && t.planned_date() < today && self.is_visible(t))
+            .map(|t| t.id)
+            .collect()
+    }
+
+    // Whether the upcoming list starts with the overdue section. Tasks completed since the last
+    // purge still show in it crossed out, so they keep the header even though they aren't counted.
+    fn has_overdue_section(&self) -> bool {
+        let today = Local::now().date_naive();
+        self.tasks
+            .iter()
+            .any(|t| t.planned_date() < today && self.is_visible(t))
+    }
+
+    pub fn ids_planned_on(&self, d: NaiveDate) -> Vec<i32> {
+        self.tasks
+            .iter()
+            .filter(|t| t.planned_date() == d && self.is_visible(t))
+            .map(|t| t.id)
+            .collect()
+    }
+
+    // Index of the task within the upcoming list, worked out without building it: the overdue
+    // header if there is one, the visible tasks sorted before this one, and a date header for
+    // every day from today up to its own
+    pub fn position_of(&self, id: i32) -> Option<usize> {
+        let today = Local::now().date_naive();
+        let task = self
+            .tasks
+            .iter()
+            .find(|t| t.id == id && self.is_visible(t))?;
+
+        let tasks_before = self
+            .tasks
+            .iter()
+            .filter(|t| self.is_visible(t))
+            .filter(|t| upcoming_order(t, task) == Ordering::Less)
+            .count();
+        let headers_before = task
+            .planned_date()
+            .signed_duration_since(today)
+            .num_days()
+            .max(-1)
+            + 1;
+        let overdue_header = usize::from(self.has_overdue_section());
+
+        Some(overdue_header + tasks_before + headers_before as usize)
+    }
+
     pub fn get_upcoming_list(&mut self, selected: usize, buffer: usize) -> Vec<TaskDate> {
         let mut current_date = Local::now().date_naive();
 
-        self.tasks.sort();
+        self.tasks.sort_by(upcoming_order);
         let mut current_task = 0;
+        let overdue_section = self.has_overdue_section();
+        let overdue_count = self.overdue_count();
 
         let mut output: Vec<TaskDate> = Vec::new();
         while output.len() < selected + buffer {
+            while current_task < self.tasks.len() && !self.is_visible(&self.tasks[current_task]) {
+                current_task += 1;
+            }
+
             let new_item: TaskDate;
-            if current_task < self.tasks.len() && self.tasks[current_task].due_date < current_date {
+            if output.is_empty() && overdue_section {
+                new_item = TaskDate::Overdue(overdue_count);
+            } else if current_task < self.tasks.len()
+                && self.tasks[current_task].planned_date() < current_date
+            {
                 if output.len() == selected {
                     self.selected_index = current_task;
                 }
@@ -61,3 +418,127 @@ impl TaskList {
         output
     }
 }
+
+// Order of tasks in the upcoming list, by the day they're planned for
+fn upcoming_order(a: &Task, b: &Task) -> Ordering {
+    a.planned_date().cmp(&b.planned_date()).then(a.cmp(b))
+}
+
+// Formats a duration as e.g. "45m", "2h" or "3h30"
+pub fn format_minutes(minutes: i32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h{:02}", h, m),
+    }
+}
+
+// Moves a date by a number of days, None if that goes past the dates chrono can represent
+pub fn add_days(date: NaiveDate, days: i64) -> Option<NaiveDate> {
+    match days >= 0 {
+        true => date.checked_add_days(Days::new(days as u64)),
+        false => date.checked_sub_days(Days::new(days.unsigned_abs())),
+    }
+}
+
+// Parses durations written as plain minutes ("90") or with units ("1h30", "2h", "45m"), which
+// have to come out positive and fit in an i32
+pub fn parse_minutes(text: &str) -> Option<i32> {
+    let text = text.trim().trim_end_matches('m');
+    let (hours, minutes) = match text.split_once('h') {
+        Some((hours, "")) => (hours.parse::<i32>().ok()?, 0),
+        Some((hours, minutes)) => (hours.parse::<i32>().ok()?, minutes.parse::<i32>().ok()?),
+        None => (0, text.parse::<i32>().ok()?),
+    };
+    if hours < 0 || minutes < 0 {
+        return None;
+    }
+    hours
+        .checked_mul(60)?
+        .checked_add(minutes)
+        .filter(|total| *total > 0)
+}
+
+pub fn format_priority(priority: i16) -> &'static str {
+    match priority {
+        1 => "low",
+        2 => "medium",
+        3 => "high",
+        _ => "None",
+    }
+}
+
+// Accepts the names, their first letters or the numbers 1 to 3
+pub fn parse_priority(text: &str) -> Option<i16> {
+    match text.to_lowercase().as_str() {
+        "low" | "l" | "1" => Some(1),
+        "medium" | "med" | "m" | "2" => Some(2),
+        "high" | "h" | "3" => Some(3),
+        _ => None,
+    }
+}
+
+pub fn format_tags(tags: &[String]) -> String {
+    tags.iter()
+        .map(|t| format!("#{}", t))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn parses_minutes() {
+        assert_eq!(parse_minutes("45"), Some(45));
+        assert_eq!(parse_minutes("45m"), Some(45));
+        assert_eq!(parse_minutes("2h"), Some(120));
+        assert_eq!(parse_minutes(" 1h30 "), Some(90));
+        assert_eq!(parse_minutes("1h30m"), Some(90));
+    }
+
+    #[test]
+    fn rejects_bad_minutes() {
+        assert_eq!(parse_minutes(""), None);
+        assert_eq!(parse_minutes("0"), None);
+        assert_eq!(parse_minutes("0h"), None);
+        assert_eq!(parse_minutes("-5"), None);
+        assert_eq!(parse_minutes("-1h30"), None);
+        assert_eq!(parse_minutes("1h-30"), None);
+        assert_eq!(parse_minutes("h"), None);
+        assert_eq!(parse_minutes("1.5h"), None);
+        assert_eq!(parse_minutes("99999999999"), None);
+        assert_eq!(parse_minutes("35791395h"), None);
+        assert_eq!(parse_minutes("35791394h59"), None);
+        assert_eq!(parse_minutes("35791394h07"), Some(i32::MAX));
+    }
+
+    #[test]
+    fn adds_days() {
+        assert_eq!(add_days(date(2024, 2, 28), 1), Some(date(2024, 2, 29)));
+        assert_eq!(add_days(date(2024, 3, 1), -1), Some(date(2024, 2, 29)));
+        assert_eq!(add_days(date(2024, 3, 1), 0), Some(date(2024, 3, 1)));
+        assert_eq!(add_days(NaiveDate::MAX, 1), None);
+        assert_eq!(add_days(NaiveDate::MIN, -1), None);
+        assert_eq!(add_days(date(2024, 3, 1), i64::MAX), None);
+        assert_eq!(add_days(date(2024, 3, 1), i64::MIN), None);
+    }
+
+    #[test]
+    fn finds_cycles() {
+        let mut list = TaskList::new();
+        list.dependencies = vec![(1, 2), (2, 3), (4, 3)];
+
+        assert!(list.creates_cycle(3, 1));
+        assert!(list.creates_cycle(2, 1));
+        assert!(list.creates_cycle(5, 5));
+        assert!(!list.creates_cycle(1, 3));
+        assert!(list.creates_cycle(3, 4));
+        assert!(!list.creates_cycle(1, 4));
+    }
+}
@@ -0,0 +1,194 @@
+use ratatui::style::{Color, Modifier, Style};
+use std::collections::HashMap;
+
+use crate::{category::Category, config::Config, status::Severity};
+
+// Named styles the UI draws with, picked by name in config.toml
+#[derive(Clone, Default)]
+pub struct Theme {
+    pub tab: Style,         // the open view in the header
+    pub header: Style,      // date headers and labels
+    pub today: Style,       // today's date header
+    pub overdue: Style,     // the overdue header and days late
+    pub warning: Style,     // priority, deadlines missed by the schedule and days over capacity
+    pub selected: Style,    // the item under the cursor
+    pub marked: Style,      // tasks in the multi-selection
+    pub completed: Style,   // completed tasks
+    pub blocked: Style,     // tasks waiting on a blocker
+    pub category: Style,    // category names, under any color set for the category
+    pub popup: Style,       // background of pop ups
+    pub status_bar: Style,  // key hints and status line
+    pub info: Style,        // messages that something went through
+    pub error: Style,       // messages that something failed, warnings use the warning style
+    pub focus_work: Style,  // the work phase in focus mode
+    pub focus_break: Style, // short and long breaks in focus mode
+}
+
+const DARK: [(&str, &str); 16] = [
+    ("tab", "bold italic lightcyan"),
+    ("header", "bold"),
+    ("today", "bold lightcyan"),
+    ("overdue", "bold lightred"),
+    ("warning", "lightred"),
+    ("selected", "bold on darkgray"),
+    ("marked", "black on lightyellow"),
+    ("completed", "darkgray crossed_out"),
+    ("blocked", "dim"),
+    ("category", "lightblue"),
+    ("popup", "none"),
+    ("status_bar", "gray"),
+    ("info", "lightgreen"),
+    ("error", "bold lightred"),
+    ("focus_work", "bold red"),
+    ("focus_break", "bold green"),
+];
+
+const LIGHT: [(&str, &str); 16] = [
+    ("tab", "bold italic blue"),
+    ("header", "bold"),
+    ("today", "bold blue"),
+    ("overdue", "bold red"),
+    ("warning", "red"),
+    ("selected", "bold on gray"),
+    ("marked", "white on blue"),
+    ("completed", "gray crossed_out"),
+    ("blocked", "dim"),
+    ("category", "magenta"),
+    ("popup", "none"),
+    ("status_bar", "darkgray"),
+    ("info", "green"),
+    ("error", "bold red"),
+    ("focus_work", "bold red"),
+    ("focus_break", "bold green"),
+];
+
+const HIGH_CONTRAST: [(&str, &str); 16] = [
+    ("tab", "bold underlined white"),
+    ("header", "bold underlined white"),
+    ("today", "bold lightyellow"),
+    ("overdue", "bold black on lightred"),
+    ("warning", "bold lightred"),
+    ("selected", "bold black on white"),
+    ("marked", "bold black on lightyellow"),
+    ("completed", "crossed_out"),
+    ("blocked", "italic"),
+    ("category", "bold lightcyan"),
+    ("popup", "white on black"),
+    ("status_bar", "bold white"),
+    ("info", "bold lightgreen"),
+    ("error", "bold white on red"),
+    ("focus_work", "bold lightred"),
+    ("focus_break", "bold lightgreen"),
+];
+
+pub const BUILT_IN: [&str; 3] = ["dark", "light", "high-contrast"];
+
+impl Theme {
+    pub fn load(config: &Config) -> Result<Theme, String> {
+        Theme::named(&config.theme, &config.themes).map_err(|e| format!("config.toml: {}", e))
+    }
+
+    // A built-in theme, or one from the [themes] tables in config.toml which override the styles
+    // of a built-in base
+    pub fn named(
+        name: &str,
+        custom: &HashMap<String, HashMap<String, String>>,
+    ) -> Result<Theme, String> {
+        let Some(styles) = custom.get(name) else {
+            return Theme::built_in(name).ok_or(format!("unknown theme \"{}\"", name));
+        };
+
+        let base = styles.get("base").map_or("dark", String::as_str);
+        let mut theme = Theme::built_in(base)
+            .ok_or(format!("theme {}: unknown base theme \"{}\"", name, base))?;
+        for (style_name, spec) in styles.iter().filter(|(k, _)| *k != "base") {
+            let style = parse_style(spec).map_err(|e| format!("theme {}: {}", name, e))?;
+            *theme
+                .style_mut(style_name)
+                .ok_or(format!("theme {}: unknown style \"{}\"", name, style_name))? = style;
+        }
+
+        Ok(theme)
+    }
+
+    fn built_in(name: &str) -> Option<Theme> {
+        let styles = match name {
+            "dark" => DARK,
+            "light" => LIGHT,
+            "high-contrast" => HIGH_CONTRAST,
+            _ => return None,
+        };
+
+        let mut theme = Theme::default();
+        for (style_name, spec) in styles {
+            *theme.style_mut(style_name).unwrap() = parse_style(spec).unwrap();
+        }
+        Some(theme)
+    }
+
+    fn style_mut(&mut self, name: &str) -> Option<&mut Style> {
+        Some(match name {
+            "tab" => &mut self.tab,
+            "header" => &mut self.header,
+            "today" => &mut self.today,
+            "overdue" => &mut self.overdue,
+            "warning" => &mut self.warning,
+            "selected" => &mut self.selected,
+            "marked" => &mut self.marked,
+            "completed" => &mut self.completed,
+            "blocked" => &mut self.blocked,
+            "category" => &mut self.category,
+            "popup" => &mut self.popup,
+            "status_bar" => &mut self.status_bar,
+            "info" => &mut self.info,
+            "error" => &mut self.error,
+            "focus_work" => &mut self.focus_work,
+            "focus_break" => &mut self.focus_break,
+            _ => return None,
+        })
+    }
+
+    // The category style in the category's own color, if it has one
+    pub fn category_style(&self, category: &Category) -> Style {
+        match category.color.as_deref().map(str::parse::<Color>) {
+            Some(Ok(color)) => self.category.fg(color),
+            _ => self.category,
+        }
+    }
+
+    pub fn severity_style(&self, severity: Severity) -> Style {
+        match severity {
+            Severity::Info => self.info,
+            Severity::Warning => self.warning,
+            Severity::Error => self.error,
+        }
+    }
+}
+
+// Reads styles written like "bold yellow on blue": any modifiers, a foreground color and "on" a
+// background color, in any order. Colors are names like "lightred", hex codes or 0-255 indexes
+pub fn parse_style(spec: &str) -> Result<Style, String> {
+    let mut style = Style::new();
+    let mut words = spec.split_whitespace();
+
+    while let Some(word) = words.next() {
+        style = match word {
+            "none" => style,
+            "bold" => style.add_modifier(Modifier::BOLD),
+            "dim" => style.add_modifier(Modifier::DIM),
+            "italic" => style.add_modifier(Modifier::ITALIC),
+            "underlined" => style.add_modifier(Modifier::UNDERLINED),
+            "reversed" => style.add_modifier(Modifier::REVERSED),
+            "crossed_out" => style.add_modifier(Modifier::CROSSED_OUT),
+            "on" => style.bg(parse_color(words.next().unwrap_or_default())?),
+            color => style.fg(parse_color(color)?),
+        };
+    }
+
+    Ok(style)
+}
+
+pub fn parse_color(word: &str) -> Result<Color, String> {
+    word.parse::<Color>()
+        .map_err(|_| format!("unknown color or style \"{}\"", word))
+}
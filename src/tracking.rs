@@ -0,0 +1,128 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::config::Config;
+
+// Open time entry, kept in the database so it keeps running across restarts
+#[derive(Clone)]
+pub struct Timer {
+    pub task_id: i32,
+    pub task_name: String,
+    pub started_at: DateTime<Utc>,
+}
+
+impl Timer {
+    pub fn elapsed(&self) -> String {
+        let seconds = Utc::now()
+            .signed_duration_since(self.started_at)
+            .num_seconds()
+            .max(0);
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        )
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PomodoroPhase {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+// Focus session cycling through work and break intervals against a single task
+pub struct Pomodoro {
+    pub task_id: i32,
+    pub task_name: String,
+    pub phase: PomodoroPhase,
+    pub phase_started_at: DateTime<Utc>,
+    pub phase_ends_at: DateTime<Utc>,
+    pub paused_remaining: Option<Duration>,
+    pub completed_work: u32, // work intervals finished this session
+}
+
+impl Pomodoro {
+    pub fn new(task_id: i32, task_name: String, config: &Config) -> Self {
+        let now = Utc::now();
+        Pomodoro {
+            task_id,
+            task_name,
+            phase: PomodoroPhase::Work,
+            phase_started_at: now,
+            phase_ends_at: now + Duration::minutes(config.pomodoro_work_minutes),
+            paused_remaining: None,
+            completed_work: 0,
+        }
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.paused_remaining
+            .unwrap_or_else(|| self.phase_ends_at.signed_duration_since(Utc::now()))
+            .max(Duration::zero())
+    }
+
+    pub fn toggle_pause(&mut self) {
+        match self.paused_remaining.take() {
+            Some(remaining) => self.phase_ends_at = Utc::now() + remaining,
+            None => self.paused_remaining = Some(self.remaining()),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.paused_remaining.is_none() && Utc::now() >= self.phase_ends_at
+    }
+
+    // Moves on to the next interval, every n-th break being a long one
+    pub fn advance(&mut self, config: &Config) {
+        let (phase, minutes) = match self.phase {
+            PomodoroPhase::Work => {
+                self.completed_work += 1;
+                if self
+                    .completed_work
+                    .is_multiple_of(config.pomodoros_before_long_break.max(1))
+                {
+                    (PomodoroPhase::LongBreak, config.pomodoro_long_break_minutes)
+                } else {
+                    (
+                        PomodoroPhase::ShortBreak,
+                        config.pomodoro_short_break_minutes,
+                    )
+                }
+            }
+            PomodoroPhase::ShortBreak | PomodoroPhase::LongBreak => {
+                (PomodoroPhase::Work, config.pomodoro_work_minutes)
+            }
+        };
+
+        let now = Utc::now();
+        self.phase = phase;
+        self.phase_started_at = now;
+        self.phase_ends_at = now + Duration::minutes(minutes);
+        self.paused_remaining = None;
+    }
+}
+
+pub struct TimeTotal {
+    pub name: String,
+    pub seconds: i64,
+    pub pomodoros: i64,
+}
+
+impl TimeTotal {
+    pub fn formatted(&self) -> String {
+        let time = format!("{}h{:02}", self.seconds / 3600, self.seconds / 60 % 60);
+        if self.pomodoros > 0 {
+            format!("{} · {} pom", time, self.pomodoros)
+        } else {
+            time
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Stats {
+    pub by_task: Vec<TimeTotal>,
+    pub by_category: Vec<TimeTotal>,
+}
@@ -1,18 +1,53 @@
 use crate::{
-    app::{App, AppMode, AppPopUp, SelectedField},
-    task::{Task, TaskDate},
+    action::Action,
+    app::{parse_date, App, AppMode, AppPopUp, ClickTarget, SelectedField, TextBox},
+    config::PanePosition,
+    key::KeySequence,
+    task::{format_minutes, format_tags, parse_minutes, Task, TaskDate, TaskField},
+    theme::Theme,
+    tracking::{PomodoroPhase, TimeTotal},
 };
-use chrono::{Local, NaiveDate};
+use chrono::{DateTime, Local, NaiveDate, Utc};
 use ratatui::{
-    prelude::{Backend, Constraint, Direction, Layout, Rect},
-    style::{Modifier, Style, Stylize},
+    prelude::{Alignment, Backend, Constraint, Direction, Layout, Rect},
+    style::{Style, Stylize},
     symbols::DOT,
     text::{Line, Span, Text},
     widgets::{Block, Borders, Clear, List, ListItem, Padding, Paragraph, Tabs},
     Frame,
 };
+use std::{rc::Rc, time::Duration};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+// Below this the screen only asks to be made bigger
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 12;
+// Width of the detail pane when it's on the right
+const DETAIL_PANE_WIDTH: u16 = 40;
+// Narrower than this, categories show their tasks under them instead of in a pane of their own
+const SINGLE_PANE_WIDTH: u16 = 60;
 
 pub fn draw<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    app.click_targets.clear();
+
+    let size = f.size();
+    if size.width < MIN_WIDTH || size.height < MIN_HEIGHT {
+        let notice = Paragraph::new(vec![
+            Line::from("Terminal too small"),
+            Line::from(format!(
+                "{}x{}, needs {}x{}",
+                size.width, size.height, MIN_WIDTH, MIN_HEIGHT
+            )),
+        ])
+        .alignment(Alignment::Center);
+        f.render_widget(
+            notice,
+            Rect::new(0, size.height.saturating_sub(2) / 2, size.width, 2).intersection(size),
+        );
+        return;
+    }
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -22,46 +57,137 @@ pub fn draw<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         ])
         .split(f.size());
 
+    let tab_titles = [
+        ("Upcoming (1)", Action::ShowUpcoming),
+        ("Categories (2)", Action::ShowCategories),
+    ];
     let tabs = Tabs::new(
-        ["Upcoming (1)", "Categories (2)"]
+        tab_titles
             .iter()
-            .cloned()
-            .map(Line::from)
+            .map(|(title, _)| Line::from(*title))
             .collect(),
     )
     .divider(DOT)
-    .highlight_style(Style::new().bold().italic())
+    .highlight_style(app.theme.tab)
     .select(if app.mode == AppMode::Upcoming { 0 } else { 1 });
     f.render_widget(tabs, chunks[0]);
 
+    // Each title is padded by a space on both sides and followed by the divider
+    let mut tab_x = chunks[0].x;
+    for (title, action) in tab_titles {
+        let tab_width = title.len() as u16 + 2;
+        app.click_targets.push((
+            Rect::new(tab_x, chunks[0].y, tab_width, 1).intersection(chunks[0]),
+            ClickTarget::Tab(action),
+        ));
+        tab_x += tab_width + 1;
+    }
+
     let footer_layout = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints(vec![Constraint::Percentage(75), Constraint::Percentage(25)])
+        .constraints(vec![Constraint::Percentage(65), Constraint::Percentage(35)])
         .split(chunks[2]);
 
-    let hint_text = Paragraph::new(app.keybind_hints.clone());
-    f.render_widget(hint_text, footer_layout[0]);
+    // The command line takes the place of the hints while it's open
+    if let Some(AppPopUp::CommandLine) = app.pop_up {
+        let command_text = Paragraph::new(format!(
+            ":{}",
+            app.command_edit
+                .view(footer_layout[0].width.saturating_sub(1))
+        ));
+        f.render_widget(command_text, footer_layout[0]);
+        f.set_cursor(
+            footer_layout[0].x + app.command_edit.cursor_column() + 1,
+            footer_layout[0].y,
+        );
+    } else {
+        let hint_text = Paragraph::new(app.keybind_hints.clone()).style(app.theme.status_bar);
+        f.render_widget(hint_text, footer_layout[0]);
+    }
 
-    let status_text = Paragraph::new(app.status_text.to_string());
+    let status_text = Paragraph::new(Line::from(vec![
+        // Start of a key sequence that's waiting for the rest
+        if app.pending_keys.is_empty() {
+            Span::raw("")
+        } else {
+            Span::styled(
+                format!("{} ", KeySequence(app.pending_keys.clone())),
+                app.theme.status_bar.bold(),
+            )
+        },
+        match &app.timer {
+            Some(timer) => Span::styled(
+                format!("[{} {}] ", timer.elapsed(), timer.task_name),
+                app.theme.status_bar.bold(),
+            ),
+            None => Span::raw(""),
+        },
+        Span::styled(app.status.current().to_string(), app.theme.status_bar),
+    ]));
     f.render_widget(status_text, footer_layout[1]);
 
     match &app.mode {
         AppMode::Upcoming => draw_upcoming(f, chunks[1], app),
         AppMode::Categories => draw_categories(f, chunks[1], app),
     }
+
+    match app.pop_up {
+        Some(AppPopUp::Stats) => draw_stats(f, app),
+        Some(AppPopUp::Focus) => draw_focus(f, app),
+        Some(AppPopUp::ConflictResolver) => draw_conflict_resolver(f, app),
+        Some(AppPopUp::History) => draw_history(f, app),
+        Some(AppPopUp::Messages) => draw_messages(f, app),
+        Some(AppPopUp::Rescheduler) => draw_rescheduler(f, app),
+        Some(AppPopUp::Shifter) => draw_shifter(f, app),
+        Some(AppPopUp::CategoryPicker) => draw_category_picker(f, app),
+        Some(AppPopUp::QuickAdd) => draw_quick_add(f, app),
+        Some(AppPopUp::CommandPalette) => draw_command_palette(f, app),
+        _ => (),
+    }
+
+    draw_toasts(f, chunks[1], app);
+
+    if app.help {
+        draw_help(f, app);
+    }
 }
 
 fn draw_upcoming<B: Backend>(f: &mut Frame<B>, r: Rect, app: &mut App) {
+    // The detail pane takes the right side, or the bottom when the list would get too narrow
+    let (r, details_area) = if app.show_details {
+        let right = app.config.detail_pane_position == PanePosition::Right
+            && r.width >= DETAIL_PANE_WIDTH * 2;
+        let parts = Layout::default()
+            .direction(if right {
+                Direction::Horizontal
+            } else {
+                Direction::Vertical
+            })
+            .constraints(if right {
+                [Constraint::Min(0), Constraint::Length(DETAIL_PANE_WIDTH)]
+            } else {
+                [
+                    Constraint::Min(0),
+                    Constraint::Length((r.height / 2).min(12)),
+                ]
+            })
+            .split(r);
+        (parts[0], Some(parts[1]))
+    } else {
+        (r, None)
+    };
+
     let task_display_height = r.height as usize;
     let task_display_width = r.width as usize;
 
+    let today = Local::now().date_naive();
+    let upcoming_list = app.task_list.get_upcoming_list(
+        app.task_list_state.selected().unwrap_or(0),
+        task_display_height,
+    );
+    let selected_ids = app.selected_ids(); // after generating the list, which moves the cursor
     let mut dates_seen = -1;
-    let list_items: Vec<ListItem> = app
-        .task_list
-        .get_upcoming_list(
-            app.task_list_state.selected().unwrap_or(0),
-            task_display_height,
-        )
+    let list_items: Vec<ListItem> = upcoming_list
         .windows(2)
         .map(|i| {
             // TODO: improve scrolling behavior
@@ -69,32 +195,123 @@ fn draw_upcoming<B: Backend>(f: &mut Frame<B>, r: Rect, app: &mut App) {
                 let mut content = vec![match &i[0] {
                     TaskDate::Date(d) => {
                         dates_seen += 1;
-                        Line::from(Span::styled(
-                            format!(
-                                "{} ({})",
-                                d.format("%b %d - %a"),
+                        let planned_minutes = app.task_list.planned_minutes(*d);
+                        Line::from(vec![
+                            Span::styled(
+                                format!(
+                                    "{} ({})",
+                                    d.format("%b %d - %a"),
+                                    if dates_seen == 0 {
+                                        "Today".to_string()
+                                    } else {
+                                        format!("+{}", dates_seen)
+                                    }
+                                ),
                                 if dates_seen == 0 {
+                                    app.theme.today
+                                } else {
+                                    app.theme.header
+                                },
+                            ),
+                            workload_span(
+                                planned_minutes,
+                                app.config.daily_capacity_minutes,
+                                &app.theme,
+                            ),
+                        ])
+                    }
+                    TaskDate::Overdue(n) => Line::from(vec![
+                        Span::styled(format!("Overdue ({})", n), app.theme.overdue),
+                        workload_span(app.task_list.overdue_minutes(), i32::MAX, &app.theme),
+                    ]),
+                    TaskDate::Task(t) => {
+                        // Labels are ranked by how long they're kept when the row gets narrow
+                        let mut labels: Vec<(u8, Span)> = Vec::new();
+
+                        if t.priority > 0 {
+                            labels.push((
+                                3,
+                                Span::styled(
+                                    format!("{}  ", "!".repeat(t.priority as usize)),
+                                    app.theme.warning.bold(),
+                                ),
+                            ));
+                        }
+                        if !t.tags.is_empty() {
+                            labels.push((0, Span::raw(format!("{}  ", format_tags(&t.tags)))));
+                        }
+
+                        let days_late = t.days_late(today);
+                        if days_late > 0 {
+                            labels.push((
+                                1,
+                                Span::styled(format!("{}d late  ", days_late), app.theme.overdue),
+                            ));
+                        }
+
+                        // Tasks planned separately from their deadline show a countdown to it
+                        if t.scheduled_date.is_some() {
+                            let countdown = t.due_date.signed_duration_since(today).num_days();
+                            let deadline = format!(
+                                "due {} ({})  ",
+                                t.due_date.format("%b %d"),
+                                if countdown == 0 {
                                     "Today".to_string()
                                 } else {
-                                    format!("+{}", dates_seen)
+                                    format!("{:+}", countdown)
                                 }
-                            ),
-                            Style::new().bold(),
-                        ))
+                            );
+                            labels.push((
+                                2,
+                                if t.scheduled_after_due() {
+                                    Span::styled(format!("!{}", deadline), app.theme.warning)
+                                } else {
+                                    Span::raw(deadline)
+                                },
+                            ));
+                        }
+
+                        // The name keeps at least half the row past the border and cursor, and
+                        // the category at most a quarter
+                        let row_width = task_display_width.saturating_sub(3);
+                        let category = truncate(&t.category.name, row_width / 4);
+                        let labels_width = |labels: &[(u8, Span)]| -> usize {
+                            labels.iter().map(|(_, l)| l.width()).sum::<usize>() + category.width()
+                        };
+                        while labels_width(&labels) > row_width / 2 {
+                            let Some(least) = (0..labels.len()).min_by_key(|i| labels[*i].0) else {
+                                break;
+                            };
+                            labels.remove(least);
+                        }
+
+                        let name_width = row_width.saturating_sub(labels_width(&labels));
+                        let name = truncate(&t.name, name_width.saturating_sub(1));
+                        let mut line = Line::from(
+                            [
+                                vec![Span::raw(format!(
+                                    "{}{}",
+                                    name,
+                                    "-".repeat(name_width.saturating_sub(name.width()))
+                                ))],
+                                labels.into_iter().map(|(_, l)| l).collect(),
+                                vec![Span::styled(
+                                    category,
+                                    app.theme.category_style(&t.category),
+                                )],
+                            ]
+                            .concat(),
+                        );
+                        if t.completed {
+                            line.patch_style(app.theme.completed);
+                        } else if app.task_list.is_blocked(t.id) {
+                            line.patch_style(app.theme.blocked);
+                        }
+                        if selected_ids.contains(&t.id) {
+                            line.patch_style(app.theme.marked);
+                        }
+                        line
                     }
-                    TaskDate::Task(t) => Line::from(Span::styled(
-                        format!(
-                            "{:-<width$}{}",
-                            t.name.clone(),
-                            t.category.name,
-                            width = task_display_width - t.category.name.len() - 3
-                        ),
-                        Style::new().add_modifier(if t.completed {
-                            Modifier::CROSSED_OUT
-                        } else {
-                            Modifier::empty()
-                        }),
-                    )),
                 }];
 
                 if let TaskDate::Date(..) = i[1] {
@@ -107,96 +324,382 @@ fn draw_upcoming<B: Backend>(f: &mut Frame<B>, r: Rect, app: &mut App) {
         .collect();
 
     let list = List::new(list_items)
-        .block(Block::default().borders(Borders::ALL))
-        .highlight_style(Style::new().italic())
+        .block(list_block(selected_ids.len(), &app.task_list.filter))
+        .highlight_style(app.theme.selected)
         .highlight_symbol(">");
     f.render_stateful_widget(list, r, &mut app.task_list_state);
 
-    if app.pop_up.is_some() {
-        match app.pop_up.as_ref().unwrap() {
-            AppPopUp::TaskEditor => {
-                draw_task_editor(f, app);
-            }
-            _ => (),
+    // Items are a line tall, plus a blank line before each date
+    let inner = Block::new().borders(Borders::ALL).inner(r);
+    let mut y = inner.y;
+    for (index, pair) in upcoming_list
+        .windows(2)
+        .enumerate()
+        .skip(app.task_list_state.offset())
+    {
+        if y >= inner.bottom() {
+            break;
         }
+        let height = if let TaskDate::Date(..) = pair[1] {
+            2
+        } else {
+            1
+        };
+        app.click_targets.push((
+            Rect::new(inner.x, y, inner.width, height).intersection(inner),
+            ClickTarget::UpcomingRow(index),
+        ));
+        y += height;
+    }
+
+    // Drawn after the list, which moves current_taskdate to the task under the cursor
+    if let Some(area) = details_area {
+        draw_task_details(f, area, app);
+    }
+
+    match app.pop_up {
+        Some(AppPopUp::TaskEditor) => draw_task_editor(f, app),
+        Some(AppPopUp::DependencyEditor) => draw_dependency_editor(f, app),
+        _ => (),
     }
 }
 
-fn draw_categories<B: Backend>(f: &mut Frame<B>, r: Rect, app: &mut App) {
-    let blocks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
-        .split(r);
+// Every field of the task under the cursor, in as many columns as it takes to fit
+fn draw_task_details<B: Backend>(f: &mut Frame<B>, r: Rect, app: &App) {
+    let block = Block::new()
+        .title("Details")
+        .borders(Borders::ALL)
+        .padding(Padding::horizontal(1));
+    let inner = block.inner(r);
+    f.render_widget(block, r);
+
+    let TaskDate::Task(t) = &app.task_list.current_taskdate else {
+        f.render_widget(Paragraph::new("No task selected"), inner);
+        return;
+    };
 
-    let categories_listitems: Vec<ListItem> = app
-        .categories
+    let task_name = |id: &i32| match app.task_list.tasks.iter().find(|t| t.id == *id) {
+        Some(t) => t.name.clone(),
+        None => format!("#{} (completed)", id),
+    };
+    let names = |ids: Vec<i32>| match ids.is_empty() {
+        true => "None".to_string(),
+        false => ids
+            .iter()
+            .map(task_name)
+            .collect::<Vec<String>>()
+            .join(", "),
+    };
+    let blocking: Vec<i32> = app
+        .task_list
+        .dependencies
         .iter()
-        .map(|i| ListItem::new(Text::from(i.name.clone())))
+        .filter(|(blocker, _)| *blocker == t.id)
+        .map(|(_, blocked)| *blocked)
         .collect();
 
-    let categories_list = List::new(categories_listitems)
-        .block(Block::new().borders(Borders::ALL))
-        .highlight_style(Style::new().italic())
-        .highlight_symbol(">");
-    f.render_stateful_widget(categories_list, blocks[0], &mut app.category_list_state);
+    // Timestamps show as blank until the ones for this task come in
+    let timestamps = app.task_timestamps.as_ref().filter(|ts| ts.task_id == t.id);
+    let timestamp = |at: Option<DateTime<Utc>>| match (timestamps, at) {
+        (None, _) => "".to_string(),
+        (Some(_), None) => "Unknown".to_string(),
+        (Some(_), Some(at)) => at.with_timezone(&Local).format("%F %H:%M").to_string(),
+    };
+
+    let mut rows = vec![("Id", t.id.to_string())];
+    rows.extend(
+        TaskField::ALL
+            .iter()
+            .filter(|field| **field != TaskField::Completed)
+            .map(|field| (field.label(), field.display(t))),
+    );
+    rows.extend([
+        (
+            "Status",
+            if t.completed {
+                "Completed"
+            } else if app.task_list.is_blocked(t.id) {
+                "Blocked"
+            } else {
+                "Open"
+            }
+            .to_string(),
+        ),
+        ("Blocked by", names(app.task_list.blockers_of(t.id))),
+        ("Blocks", names(blocking)),
+        (
+            "Created",
+            timestamp(timestamps.and_then(|ts| ts.created_at)),
+        ),
+        (
+            "Updated",
+            timestamp(timestamps.and_then(|ts| ts.updated_at)),
+        ),
+        (
+            "Completed",
+            match t.completed {
+                true => timestamp(timestamps.and_then(|ts| ts.completed_at)),
+                false => "No".to_string(),
+            },
+        ),
+    ]);
+
+    let rows_per_column = (inner.height as usize).max(1);
+    let column_count = rows.len().div_ceil(rows_per_column);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![
+            Constraint::Ratio(1, column_count as u32);
+            column_count
+        ])
+        .split(inner);
+
+    let label_width = 11;
+    for (column, chunk) in columns.iter().zip(rows.chunks(rows_per_column)) {
+        let value_width = (column.width as usize).saturating_sub(label_width + 1);
+        let lines: Vec<Line> = chunk
+            .iter()
+            .map(|(label, value)| {
+                Line::from(vec![
+                    Span::styled(format!("{:<label_width$}", label), app.theme.header),
+                    Span::raw(truncate(value, value_width)),
+                ])
+            })
+            .collect();
+        f.render_widget(Paragraph::new(lines), *column);
+    }
+}
+
+// Task lists are titled with the selection size and the active filter, if any
+fn list_block(selected: usize, filter: &Option<String>) -> Block<'static> {
+    let mut title = Vec::new();
+    if selected > 0 {
+        title.push(format!("{} selected", selected));
+    }
+    if let Some(filter) = filter {
+        title.push(format!("filter: {}", filter));
+    }
+
+    let block = Block::default().borders(Borders::ALL);
+    if title.is_empty() {
+        block
+    } else {
+        block.title(title.join(" · "))
+    }
+}
+
+// Cuts text down to width columns, ending it with an ellipsis if anything had to go
+fn truncate(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+
+    let mut shown_width = 0;
+    let mut shown: String = text
+        .graphemes(true)
+        .take_while(|g| {
+            shown_width += g.width();
+            shown_width < width
+        })
+        .collect();
+    if width > 0 {
+        shown.truncate(shown.trim_end().len());
+        shown.push('…');
+    }
+    shown
+}
+
+// Keys for the main actions of the open pop up, shown along its bottom edge
+fn popup_hints(app: &App, actions: &[Action]) -> String {
+    app.keymap.hints_for(app.pop_up_contexts(), actions)
+}
+
+// Centers a pop up of the given size in the frame, shrinking it on screens too small for it
+fn popup_area(frame: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(frame.width);
+    let height = height.min(frame.height);
+    Rect::new(
+        frame.x + (frame.width - width) / 2,
+        frame.y + (frame.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+// Centers a pop up over the view and clears the area under it
+fn open_popup<B: Backend>(f: &mut Frame<B>, width: u16, height: u16, theme: &Theme) -> Rect {
+    let area = popup_area(f.size(), width, height);
+    clear_popup(f, area, theme);
+    area
+}
+
+// Splits the bottom line off a pop up for its key hints
+fn split_hint_line(area: Rect) -> Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Min(0), Constraint::Length(1)])
+        .split(area)
+}
+
+// Blanks out the view under a pop up and fills it with the theme's pop up background
+fn clear_popup<B: Backend>(f: &mut Frame<B>, area: Rect, theme: &Theme) {
+    f.render_widget(Clear, area);
+    f.render_widget(Block::new().style(theme.popup), area);
+}
 
+// Areas of the rows shown in a bordered list with an item per line, scrolled down to offset
+fn list_rows(area: Rect, offset: usize, len: usize) -> Vec<(usize, Rect)> {
+    let inner = Block::new().borders(Borders::ALL).inner(area);
+    (offset..len)
+        .zip(inner.top()..inner.bottom())
+        .map(|(index, y)| (index, Rect::new(inner.x, y, inner.width, 1)))
+        .collect()
+}
+
+// Total estimated work for a header, highlighted when it goes over capacity
+fn workload_span(minutes: i32, capacity: i32, theme: &Theme) -> Span<'static> {
+    if minutes == 0 {
+        Span::raw("")
+    } else if minutes > capacity {
+        Span::styled(
+            format!(" · {}", format_minutes(minutes)),
+            theme.warning.bold().reversed(),
+        )
+    } else {
+        Span::styled(format!(" · {}", format_minutes(minutes)), theme.header)
+    }
+}
+
+fn draw_categories<B: Backend>(f: &mut Frame<B>, r: Rect, app: &mut App) {
+    let selected_category = app.category_list_state.selected().unwrap();
     let current_category_tasks: Vec<&Task> = app
         .task_list
         .tasks
         .iter()
-        .filter(|t| t.category.id == app.categories[app.category_list_state.selected().unwrap()].id)
+        .filter(|t| Some(t.category.id) == app.categories.get(selected_category).map(|c| c.id))
+        .filter(|t| app.task_list.is_visible(t))
         .collect();
 
-    let current_category_listitems: Vec<ListItem> = current_category_tasks
-        .iter()
-        .map(|t| ListItem::new(Text::from(t.name.clone())))
-        .collect();
+    let selected_ids = app.selected_ids();
+    let task_item = |t: &Task, indent: &str, width: usize| {
+        ListItem::new(Text::from(format!(
+            "{}{}",
+            indent,
+            truncate(&t.name, width)
+        )))
+        .style(if selected_ids.contains(&t.id) {
+            app.theme.marked
+        } else if t.completed {
+            app.theme.completed
+        } else {
+            Style::new()
+        })
+    };
 
-    let current_category_list = List::new(current_category_listitems)
-        .block(Block::new().borders(Borders::ALL))
-        .highlight_style(Style::new().italic())
-        .highlight_symbol(">");
-    f.render_widget(current_category_list, blocks[1]);
+    // Too narrow for two panes, so the highlighted category's tasks are listed under it instead.
+    // Only categories come before it, so the cursor stays at the category's own index
+    if r.width < SINGLE_PANE_WIDTH {
+        let item_width = (r.width as usize).saturating_sub(3);
+        let mut items = Vec::new();
+        let mut targets = Vec::new();
+        for (index, category) in app.categories.iter().enumerate() {
+            items.push(ListItem::new(Line::styled(
+                truncate(&category.name, item_width),
+                app.theme.category_style(category),
+            )));
+            targets.push(ClickTarget::Category(index));
 
-    if app.pop_up.is_some() {
-        match app.pop_up.as_ref().unwrap() {
-            AppPopUp::CategoryEditor => {
-                draw_category_editor(f, app);
+            if index == selected_category {
+                for t in &current_category_tasks {
+                    items.push(task_item(t, "  ", item_width.saturating_sub(2)));
+                    targets.push(ClickTarget::CategoryTask(t.id));
+                }
             }
-            _ => (),
         }
+
+        let list = List::new(items)
+            .block(list_block(selected_ids.len(), &app.task_list.filter))
+            .highlight_style(app.theme.selected)
+            .highlight_symbol(">");
+        f.render_stateful_widget(list, r, &mut app.category_list_state);
+        for (index, row) in list_rows(r, app.category_list_state.offset(), targets.len()) {
+            app.click_targets.push((row, targets[index]));
+        }
+    } else {
+        let blocks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+            .split(r);
+
+        let category_width = (blocks[0].width as usize).saturating_sub(3);
+        let categories_listitems: Vec<ListItem> = app
+            .categories
+            .iter()
+            .map(|i| {
+                ListItem::new(Line::styled(
+                    truncate(&i.name, category_width),
+                    app.theme.category_style(i),
+                ))
+            })
+            .collect();
+
+        let categories_list = List::new(categories_listitems)
+            .block(Block::new().borders(Borders::ALL))
+            .highlight_style(app.theme.selected)
+            .highlight_symbol(">");
+        f.render_stateful_widget(categories_list, blocks[0], &mut app.category_list_state);
+        for (index, row) in list_rows(
+            blocks[0],
+            app.category_list_state.offset(),
+            app.categories.len(),
+        ) {
+            app.click_targets.push((row, ClickTarget::Category(index)));
+        }
+
+        let task_width = (blocks[1].width as usize).saturating_sub(2);
+        let current_category_listitems: Vec<ListItem> = current_category_tasks
+            .iter()
+            .map(|t| task_item(t, "", task_width))
+            .collect();
+
+        let current_category_list = List::new(current_category_listitems)
+            .block(list_block(selected_ids.len(), &app.task_list.filter));
+        f.render_widget(current_category_list, blocks[1]);
+        for (index, row) in list_rows(blocks[1], 0, current_category_tasks.len()) {
+            app.click_targets.push((
+                row,
+                ClickTarget::CategoryTask(current_category_tasks[index].id),
+            ));
+        }
+    }
+
+    if let Some(AppPopUp::CategoryEditor) = app.pop_up {
+        draw_category_editor(f, app);
     }
 }
 
 fn draw_task_editor<B: Backend>(f: &mut Frame<B>, app: &mut App) {
     // NOTE: calculate required lengths BEFORE rendering
-    let task_editor_width = 50; // TODO: need to be changed to minimums instead of constants
+    let task_editor_width = 50;
     let category_editor_height = if app.task_edit_field == SelectedField::Category {
         8
     } else {
         3
     };
-    let task_editor_height = 9 + category_editor_height;
-
-    let frame_size = f.size();
-    let editor_area = Rect::new(
-        frame_size.width.saturating_sub(task_editor_width) / 2,
-        frame_size.height.saturating_sub(task_editor_height) / 2,
-        task_editor_width.min(frame_size.width),
-        task_editor_height.min(frame_size.height),
-    );
-    f.render_widget(Clear, editor_area);
+    let task_editor_height = 15 + category_editor_height;
 
-    let hint_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints(vec![Constraint::Min(0), Constraint::Length(1)])
-        .split(editor_area);
+    let editor_area = open_popup(f, task_editor_width, task_editor_height, &app.theme);
+
+    let hint_layout = split_hint_line(editor_area);
 
     f.render_widget(
         Block::new()
             .title(if app.editing_task {
                 if let TaskDate::Task(t) = &app.task_list.current_taskdate {
-                    format!("Edit \"{}\"", t.name) // TODO: overflow behavior
+                    format!(
+                        "Edit \"{}\"",
+                        truncate(&t.name, editor_area.width.saturating_sub(9) as usize)
+                    )
                 } else {
                     "Edit Task".to_string()
                 }
@@ -207,7 +710,21 @@ fn draw_task_editor<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         hint_layout[0],
     );
 
-    let hint = Paragraph::new("Scroll[Tab]  Submit[Enter]");
+    let due_date = parse_date(&app.year_edit, &app.month_edit, &app.date_edit);
+    let scheduled_date = parse_date(
+        &app.scheduled_year_edit,
+        &app.scheduled_month_edit,
+        &app.scheduled_date_edit,
+    );
+    let hint = Paragraph::new(Line::from(vec![
+        Span::raw(popup_hints(app, &[Action::NextField, Action::Save])),
+        match (due_date, scheduled_date) {
+            (Some(due), Some(scheduled)) if scheduled > due => {
+                Span::styled("  Scheduled after due!", app.theme.warning)
+            }
+            _ => Span::raw(""),
+        },
+    ]));
     f.render_widget(hint, hint_layout[1]);
 
     let vertical_layout = Layout::default()
@@ -216,12 +733,17 @@ fn draw_task_editor<B: Backend>(f: &mut Frame<B>, app: &mut App) {
             Constraint::Length(3),
             Constraint::Length(3),
             Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
         ])
         .margin(1)
         .split(hint_layout[0]);
 
-    let textarea = Paragraph::new(app.name_edit.text.clone())
-        .block(Block::new().title("Name").borders(Borders::ALL));
+    let textarea = Paragraph::new(
+        app.name_edit
+            .view(vertical_layout[0].width.saturating_sub(2)),
+    )
+    .block(Block::new().title("Name").borders(Borders::ALL));
     f.render_widget(textarea, vertical_layout[0]);
 
     let date_blocks = Layout::default()
@@ -251,6 +773,7 @@ fn draw_task_editor<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         let old_date = Paragraph::new(match &app.task_list.current_taskdate {
             TaskDate::Task(t) => t.due_date.format("%F (%a)").to_string(),
             TaskDate::Date(d) => d.to_string(),
+            TaskDate::Overdue(_) => "".to_string(),
         })
         .block(Block::new().title("Due").borders(Borders::ALL));
         f.render_widget(old_date, date_blocks[0]);
@@ -259,40 +782,83 @@ fn draw_task_editor<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         f.render_widget(arrow, date_blocks[1]);
     }
 
-    let year = Paragraph::new(app.year_edit.text.clone())
-        .block(Block::new().title("Y").borders(Borders::ALL));
-    f.render_widget(year, date_layout[0]);
-
-    let month = Paragraph::new(app.month_edit.text.clone())
-        .block(Block::new().title("M").borders(Borders::ALL));
-    f.render_widget(month, date_layout[1]);
+    draw_date_fields(
+        f,
+        &date_layout,
+        if app.editing_task { "W" } else { "Due" },
+        &app.year_edit,
+        &app.month_edit,
+        &app.date_edit,
+    );
 
-    let day = Paragraph::new(app.date_edit.text.clone())
-        .block(Block::new().title("D").borders(Borders::ALL));
-    f.render_widget(day, date_layout[2]);
+    let scheduled_blocks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![
+            Constraint::Max(18),
+            Constraint::Max(4),
+            Constraint::Min(0),
+        ])
+        .split(vertical_layout[2]);
 
-    let weekday = Paragraph::new(
-        if let Some(parsed_date) = NaiveDate::from_ymd_opt(
-            app.year_edit.text.parse::<i32>().unwrap_or(i32::MAX),
-            app.month_edit.text.parse::<u32>().unwrap_or(0),
-            app.date_edit.text.parse::<u32>().unwrap_or(0),
-        ) {
-            format!("{} ({})", parsed_date.format("%a"), {
-                let offset = parsed_date
-                    .signed_duration_since(Local::now().date_naive()) // TODO: overflow and logic is super messy
-                    .num_days();
-                if offset == 0 {
-                    "Today".to_string()
-                } else {
-                    format!("{:+}", offset)
-                }
-            })
+    let scheduled_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![
+            Constraint::Max(6),
+            Constraint::Max(4),
+            Constraint::Max(4),
+            Constraint::Min(0),
+        ])
+        .split(if app.editing_task {
+            scheduled_blocks[2]
         } else {
-            "DATE INVALID".to_string()
-        },
+            vertical_layout[2]
+        });
+
+    if app.editing_task {
+        let old_scheduled = Paragraph::new(match &app.task_list.current_taskdate {
+            TaskDate::Task(Task {
+                scheduled_date: Some(d),
+                ..
+            }) => d.format("%F (%a)").to_string(),
+            _ => "None".to_string(),
+        })
+        .block(Block::new().title("Scheduled").borders(Borders::ALL));
+        f.render_widget(old_scheduled, scheduled_blocks[0]);
+
+        let arrow = Paragraph::new("->").block(Block::new().padding(Padding::uniform(1)));
+        f.render_widget(arrow, scheduled_blocks[1]);
+    }
+
+    draw_date_fields(
+        f,
+        &scheduled_layout,
+        if app.editing_task { "W" } else { "Scheduled" },
+        &app.scheduled_year_edit,
+        &app.scheduled_month_edit,
+        &app.scheduled_date_edit,
+    );
+
+    let estimate_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Max(12), Constraint::Min(0)])
+        .split(vertical_layout[3]);
+
+    let estimate = Paragraph::new(
+        app.estimate_edit
+            .view(estimate_layout[0].width.saturating_sub(2)),
     )
-    .block(Block::new().title("W").borders(Borders::ALL));
-    f.render_widget(weekday, date_layout[3]);
+    .block(Block::new().title("Estimate").borders(Borders::ALL));
+    f.render_widget(estimate, estimate_layout[0]);
+
+    let estimate_preview = Paragraph::new(if app.estimate_edit.text.is_empty() {
+        "NONE".to_string()
+    } else if let Some(minutes) = parse_minutes(&app.estimate_edit.text) {
+        format_minutes(minutes)
+    } else {
+        "ESTIMATE INVALID".to_string()
+    })
+    .block(Block::new().padding(Padding::uniform(1)));
+    f.render_widget(estimate_preview, estimate_layout[1]);
 
     if app.task_edit_field == SelectedField::Category {
         let category = List::new(
@@ -302,58 +868,99 @@ fn draw_task_editor<B: Backend>(f: &mut Frame<B>, app: &mut App) {
                 .collect::<Vec<ListItem>>(),
         )
         .block(Block::new().title("Category").borders(Borders::ALL))
-        .highlight_style(Style::new().italic())
+        .highlight_style(app.theme.selected)
         .highlight_symbol(">");
-        f.render_stateful_widget(category, vertical_layout[2], &mut app.category_edit_state);
+        f.render_stateful_widget(category, vertical_layout[4], &mut app.category_edit_state);
     } else {
         let current_category = Paragraph::new(
-            app.categories[app.category_edit_state.selected().unwrap()]
-                .name
-                .clone(),
+            app.categories
+                .get(app.category_edit_state.selected().unwrap_or(0))
+                .map(|c| c.name.clone())
+                .unwrap_or_default(),
         )
         .block(Block::new().title("Category").borders(Borders::ALL));
-        f.render_widget(current_category, vertical_layout[2]);
+        f.render_widget(current_category, vertical_layout[4]);
+    }
+
+    app.click_targets.extend([
+        (vertical_layout[0], ClickTarget::Field(SelectedField::Name)),
+        (date_layout[0], ClickTarget::Field(SelectedField::Year)),
+        (date_layout[1], ClickTarget::Field(SelectedField::Month)),
+        (date_layout[2], ClickTarget::Field(SelectedField::Date)),
+        (
+            scheduled_layout[0],
+            ClickTarget::Field(SelectedField::ScheduledYear),
+        ),
+        (
+            scheduled_layout[1],
+            ClickTarget::Field(SelectedField::ScheduledMonth),
+        ),
+        (
+            scheduled_layout[2],
+            ClickTarget::Field(SelectedField::ScheduledDate),
+        ),
+        (
+            estimate_layout[0],
+            ClickTarget::Field(SelectedField::Estimate),
+        ),
+        (
+            vertical_layout[4],
+            ClickTarget::Field(SelectedField::Category),
+        ),
+    ]);
+    if app.task_edit_field == SelectedField::Category {
+        for (index, row) in list_rows(
+            vertical_layout[4],
+            app.category_edit_state.offset(),
+            app.categories.len(),
+        ) {
+            app.click_targets
+                .push((row, ClickTarget::CategoryOption(index)));
+        }
     }
 
     let (active_area, active_index) = match app.task_edit_field {
-        SelectedField::Name => (vertical_layout[0], app.name_edit.index),
-        SelectedField::Year => (date_layout[0], app.year_edit.index),
-        SelectedField::Month => (date_layout[1], app.month_edit.index),
-        SelectedField::Date => (date_layout[2], app.date_edit.index),
+        SelectedField::Name => (vertical_layout[0], app.name_edit.cursor_column()),
+        SelectedField::Year => (date_layout[0], app.year_edit.cursor_column()),
+        SelectedField::Month => (date_layout[1], app.month_edit.cursor_column()),
+        SelectedField::Date => (date_layout[2], app.date_edit.cursor_column()),
+        SelectedField::ScheduledYear => {
+            (scheduled_layout[0], app.scheduled_year_edit.cursor_column())
+        }
+        SelectedField::ScheduledMonth => (
+            scheduled_layout[1],
+            app.scheduled_month_edit.cursor_column(),
+        ),
+        SelectedField::ScheduledDate => {
+            (scheduled_layout[2], app.scheduled_date_edit.cursor_column())
+        }
+        SelectedField::Estimate => (estimate_layout[0], app.estimate_edit.cursor_column()),
         SelectedField::Category => (Rect::default(), 0),
     };
 
     if app.task_edit_field != SelectedField::Category {
-        f.set_cursor(active_area.x + active_index as u16 + 1, active_area.y + 1);
+        f.set_cursor(active_area.x + active_index + 1, active_area.y + 1);
     }
 }
 
 fn draw_category_editor<B: Backend>(f: &mut Frame<B>, app: &mut App) {
-    // NOTE: calculate required lengths BEFORE rendering
-    let category_editor_width = 50; // TODO: need to be changed to minimums instead of constants
-    let category_editor_height = 6;
-
-    let frame_size = f.size();
-    let editor_area = Rect::new(
-        frame_size.width.saturating_sub(category_editor_width) / 2,
-        frame_size.height.saturating_sub(category_editor_height) / 2,
-        category_editor_width.min(frame_size.width),
-        category_editor_height.min(frame_size.height),
-    );
-    f.render_widget(Clear, editor_area);
+    let editor_area = open_popup(f, 50, 6, &app.theme);
 
-    let hint_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints(vec![Constraint::Min(0), Constraint::Length(1)])
-        .split(editor_area);
+    let hint_layout = split_hint_line(editor_area);
 
     f.render_widget(
         Block::new()
             .title(if app.editing_category {
                 format!(
                     "Edit \"{}\"",
-                    app.categories[app.category_list_state.selected().unwrap()].name
-                ) // TODO: overflow behavior
+                    truncate(
+                        &app.categories
+                            .get(app.category_list_state.selected().unwrap_or(0))
+                            .map(|c| c.name.clone())
+                            .unwrap_or_default(),
+                        editor_area.width.saturating_sub(9) as usize
+                    )
+                )
             } else {
                 "Add Category".to_string()
             })
@@ -361,7 +968,198 @@ fn draw_category_editor<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         hint_layout[0],
     );
 
-    let hint = Paragraph::new("Submit[Enter]");
+    let hint = Paragraph::new(popup_hints(app, &[Action::Confirm]));
+    f.render_widget(hint, hint_layout[1]);
+
+    let vertical_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(3)])
+        .margin(1)
+        .split(hint_layout[0]);
+
+    let textarea = Paragraph::new(
+        app.name_edit
+            .view(vertical_layout[0].width.saturating_sub(2)),
+    )
+    .block(Block::new().title("Name").borders(Borders::ALL));
+    f.render_widget(textarea, vertical_layout[0]);
+
+    let (active_area, active_index) = (vertical_layout[0], app.name_edit.cursor_column());
+    f.set_cursor(active_area.x + active_index + 1, active_area.y + 1);
+}
+
+// Renders Y/M/D boxes plus a weekday/offset preview into the first four areas of date_layout
+fn draw_date_fields<B: Backend>(
+    f: &mut Frame<B>,
+    date_layout: &[Rect],
+    weekday_title: &str,
+    year_edit: &TextBox,
+    month_edit: &TextBox,
+    date_edit: &TextBox,
+) {
+    let year =
+        Paragraph::new(year_edit.text.clone()).block(Block::new().title("Y").borders(Borders::ALL));
+    f.render_widget(year, date_layout[0]);
+
+    let month = Paragraph::new(month_edit.text.clone())
+        .block(Block::new().title("M").borders(Borders::ALL));
+    f.render_widget(month, date_layout[1]);
+
+    let day =
+        Paragraph::new(date_edit.text.clone()).block(Block::new().title("D").borders(Borders::ALL));
+    f.render_widget(day, date_layout[2]);
+
+    let weekday = Paragraph::new(
+        if let Some(parsed_date) = NaiveDate::from_ymd_opt(
+            year_edit.text.parse::<i32>().unwrap_or(i32::MAX),
+            month_edit.text.parse::<u32>().unwrap_or(0),
+            date_edit.text.parse::<u32>().unwrap_or(0),
+        ) {
+            format!("{} ({})", parsed_date.format("%a"), {
+                let offset = parsed_date
+                    .signed_duration_since(Local::now().date_naive()) // TODO: overflow and logic is super messy
+                    .num_days();
+                if offset == 0 {
+                    "Today".to_string()
+                } else {
+                    format!("{:+}", offset)
+                }
+            })
+        } else if year_edit.text.is_empty()
+            && month_edit.text.is_empty()
+            && date_edit.text.is_empty()
+        {
+            "NONE".to_string()
+        } else {
+            "DATE INVALID".to_string()
+        },
+    )
+    .block(Block::new().title(weekday_title).borders(Borders::ALL));
+    f.render_widget(weekday, date_layout[3]);
+}
+
+fn draw_rescheduler<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let editor_area = open_popup(f, 50, 6, &app.theme);
+
+    let hint_layout = split_hint_line(editor_area);
+
+    f.render_widget(
+        Block::new()
+            .title(format!("Reschedule {} Tasks", app.bulk_ids.len()))
+            .borders(Borders::ALL),
+        hint_layout[0],
+    );
+
+    let hint = Paragraph::new(popup_hints(app, &[Action::NextField, Action::Confirm]));
+    f.render_widget(hint, hint_layout[1]);
+
+    let vertical_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(3)])
+        .margin(1)
+        .split(hint_layout[0]);
+
+    let date_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![
+            Constraint::Max(6),
+            Constraint::Max(4),
+            Constraint::Max(4),
+            Constraint::Min(0),
+        ])
+        .split(vertical_layout[0]);
+
+    draw_date_fields(
+        f,
+        &date_layout,
+        "W",
+        &app.year_edit,
+        &app.month_edit,
+        &app.date_edit,
+    );
+
+    app.click_targets.extend([
+        (date_layout[0], ClickTarget::Field(SelectedField::Year)),
+        (date_layout[1], ClickTarget::Field(SelectedField::Month)),
+        (date_layout[2], ClickTarget::Field(SelectedField::Date)),
+    ]);
+
+    let (active_area, active_index) = match app.task_edit_field {
+        SelectedField::Year => (date_layout[0], app.year_edit.cursor_column()),
+        SelectedField::Month => (date_layout[1], app.month_edit.cursor_column()),
+        _ => (date_layout[2], app.date_edit.cursor_column()),
+    };
+    f.set_cursor(active_area.x + active_index + 1, active_area.y + 1);
+}
+
+fn draw_quick_add<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let quick_add_area = open_popup(f, 70, 13, &app.theme);
+
+    let hint_layout = split_hint_line(quick_add_area);
+
+    f.render_widget(
+        Block::new().title("Quick Add").borders(Borders::ALL),
+        hint_layout[0],
+    );
+
+    let hint = Paragraph::new(format!(
+        "@category  date  !priority  #tag  ~estimate  {}",
+        popup_hints(app, &[Action::Confirm])
+    ));
+    f.render_widget(hint, hint_layout[1]);
+
+    let vertical_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(3), Constraint::Min(0)])
+        .margin(1)
+        .split(hint_layout[0]);
+
+    let textarea = Paragraph::new(
+        app.quick_add_edit
+            .view(vertical_layout[0].width.saturating_sub(2)),
+    )
+    .block(Block::new().borders(Borders::ALL));
+    f.render_widget(textarea, vertical_layout[0]);
+
+    // Live preview of what [Enter] would create
+    let preview: Vec<Line> = match app.quick_add_task(&app.quick_add_edit.text) {
+        Ok(t) => [
+            TaskField::Name,
+            TaskField::DueDate,
+            TaskField::Category,
+            TaskField::Priority,
+            TaskField::Tags,
+            TaskField::Estimate,
+        ]
+        .iter()
+        .map(|field| {
+            Line::from(vec![
+                Span::styled(format!(" {:<10}", field.label()), app.theme.header),
+                Span::raw(field.display(&t)),
+            ])
+        })
+        .collect(),
+        Err(e) => vec![Line::styled(format!(" {}", e), app.theme.warning)],
+    };
+    f.render_widget(Paragraph::new(preview), vertical_layout[1]);
+
+    let (active_area, active_index) = (vertical_layout[0], app.quick_add_edit.cursor_column());
+    f.set_cursor(active_area.x + active_index + 1, active_area.y + 1);
+}
+
+fn draw_shifter<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let shifter_area = open_popup(f, 40, 6, &app.theme);
+
+    let hint_layout = split_hint_line(shifter_area);
+
+    f.render_widget(
+        Block::new()
+            .title(format!("Shift {} Tasks", app.bulk_ids.len()))
+            .borders(Borders::ALL),
+        hint_layout[0],
+    );
+
+    let hint = Paragraph::new(popup_hints(app, &[Action::Confirm]));
     f.render_widget(hint, hint_layout[1]);
 
     let vertical_layout = Layout::default()
@@ -370,10 +1168,559 @@ fn draw_category_editor<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         .margin(1)
         .split(hint_layout[0]);
 
-    let textarea = Paragraph::new(app.name_edit.text.clone())
-        .block(Block::new().title("Name").borders(Borders::ALL));
+    let textarea = Paragraph::new(
+        app.shift_edit
+            .view(vertical_layout[0].width.saturating_sub(2)),
+    )
+    .block(Block::new().title("Days").borders(Borders::ALL));
     f.render_widget(textarea, vertical_layout[0]);
 
-    let (active_area, active_index) = (vertical_layout[0], app.name_edit.index);
-    f.set_cursor(active_area.x + active_index as u16 + 1, active_area.y + 1);
+    let (active_area, active_index) = (vertical_layout[0], app.shift_edit.cursor_column());
+    f.set_cursor(active_area.x + active_index + 1, active_area.y + 1);
+}
+
+fn draw_category_picker<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let picker_area = open_popup(f, 40, 12, &app.theme);
+
+    let hint_layout = split_hint_line(picker_area);
+
+    let hint = Paragraph::new(popup_hints(app, &[Action::Confirm]));
+    f.render_widget(hint, hint_layout[1]);
+
+    let categories = List::new(
+        app.categories
+            .iter()
+            .map(|c| ListItem::new(Text::from(c.name.clone())))
+            .collect::<Vec<ListItem>>(),
+    )
+    .block(
+        Block::new()
+            .title(format!("Move {} Tasks To", app.bulk_ids.len()))
+            .borders(Borders::ALL),
+    )
+    .highlight_style(app.theme.selected)
+    .highlight_symbol(">");
+    f.render_stateful_widget(categories, hint_layout[0], &mut app.category_edit_state);
+}
+
+fn draw_dependency_editor<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let editor_area = open_popup(f, 50, 16, &app.theme);
+
+    let hint_layout = split_hint_line(editor_area);
+
+    let Some(editing_task) = app.selected_task().cloned() else {
+        return;
+    };
+    f.render_widget(
+        Block::new()
+            .title(format!(
+                "\"{}\" Blocked By",
+                truncate(
+                    &editing_task.name,
+                    editor_area.width.saturating_sub(15) as usize
+                )
+            ))
+            .borders(Borders::ALL),
+        hint_layout[0],
+    );
+
+    let hint = Paragraph::new(popup_hints(
+        app,
+        &[Action::ScrollDown, Action::ScrollUp, Action::Confirm],
+    ));
+    f.render_widget(hint, hint_layout[1]);
+
+    let vertical_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(3), Constraint::Min(0)])
+        .margin(1)
+        .split(hint_layout[0]);
+
+    let search = Paragraph::new(
+        app.search_edit
+            .view(vertical_layout[0].width.saturating_sub(2)),
+    )
+    .block(Block::new().title("Search").borders(Borders::ALL));
+    f.render_widget(search, vertical_layout[0]);
+
+    let blockers = app.task_list.blockers_of(editing_task.id);
+    let candidates = List::new(
+        app.task_list
+            .fuzzy_search(&app.search_edit.text, editing_task.id)
+            .iter()
+            .map(|t| {
+                ListItem::new(Text::from(format!(
+                    "[{}] {} ({})",
+                    if blockers.contains(&t.id) { "x" } else { " " },
+                    t.name,
+                    t.category.name
+                )))
+            })
+            .collect::<Vec<ListItem>>(),
+    )
+    .block(Block::new().borders(Borders::ALL))
+    .highlight_style(app.theme.selected)
+    .highlight_symbol(">");
+    f.render_stateful_widget(
+        candidates,
+        vertical_layout[1],
+        &mut app.dependency_list_state,
+    );
+
+    f.set_cursor(
+        vertical_layout[0].x + app.search_edit.cursor_column() + 1,
+        vertical_layout[0].y + 1,
+    );
+}
+
+fn draw_command_palette<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let palette_area = open_popup(f, 50, 16, &app.theme);
+
+    let hint_layout = split_hint_line(palette_area);
+
+    f.render_widget(
+        Block::new().title("Command Palette").borders(Borders::ALL),
+        hint_layout[0],
+    );
+
+    let hint = Paragraph::new(popup_hints(
+        app,
+        &[Action::ScrollDown, Action::ScrollUp, Action::Confirm],
+    ));
+    f.render_widget(hint, hint_layout[1]);
+
+    let vertical_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(3), Constraint::Min(0)])
+        .margin(1)
+        .split(hint_layout[0]);
+
+    let search = Paragraph::new(
+        app.palette_edit
+            .view(vertical_layout[0].width.saturating_sub(2)),
+    )
+    .block(Block::new().title("Search").borders(Borders::ALL));
+    f.render_widget(search, vertical_layout[0]);
+
+    // Keys are right aligned after the action's name
+    let row_width = vertical_layout[1].width.saturating_sub(3) as usize;
+    let actions = List::new(
+        app.palette_actions()
+            .iter()
+            .map(|(action, keys)| {
+                let keys = keys
+                    .iter()
+                    .map(|k| k.to_string())
+                    .collect::<Vec<String>>()
+                    .join("/");
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!(
+                        "{:<width$}",
+                        action.name(),
+                        width = row_width.saturating_sub(keys.len())
+                    )),
+                    Span::styled(keys, app.theme.header),
+                ]))
+            })
+            .collect::<Vec<ListItem>>(),
+    )
+    .block(Block::new().borders(Borders::ALL))
+    .highlight_style(app.theme.selected)
+    .highlight_symbol(">");
+    f.render_stateful_widget(actions, vertical_layout[1], &mut app.palette_list_state);
+
+    f.set_cursor(
+        vertical_layout[0].x + app.palette_edit.cursor_column() + 1,
+        vertical_layout[0].y + 1,
+    );
+}
+
+fn draw_stats<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let stats_area = open_popup(f, 70, 20, &app.theme);
+
+    f.render_widget(
+        Block::new().title("Time Tracked").borders(Borders::ALL),
+        stats_area,
+    );
+
+    let column_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Percentage(60), Constraint::Percentage(40)])
+        .margin(1)
+        .split(stats_area);
+
+    let totals_list = |totals: &[TimeTotal], width: u16| -> Vec<ListItem> {
+        totals
+            .iter()
+            .map(|t| {
+                let time = t.formatted();
+                ListItem::new(Text::from(format!(
+                    "{:-<width$}{}",
+                    t.name,
+                    time,
+                    width = (width as usize).saturating_sub(time.len() + 3)
+                )))
+            })
+            .collect()
+    };
+
+    let by_task = List::new(totals_list(&app.stats.by_task, column_layout[0].width))
+        .block(Block::new().title("By Task").borders(Borders::ALL))
+        .highlight_style(app.theme.selected)
+        .highlight_symbol(">");
+    f.render_stateful_widget(by_task, column_layout[0], &mut app.stats_list_state);
+
+    let by_category = List::new(totals_list(&app.stats.by_category, column_layout[1].width))
+        .block(Block::new().title("By Category").borders(Borders::ALL));
+    f.render_widget(by_category, column_layout[1]);
+}
+
+fn draw_conflict_resolver<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let Some(conflict) = app.conflicts.first() else {
+        return;
+    };
+
+    let resolver_area = open_popup(f, 80, 12, &app.theme);
+
+    let hint_layout = split_hint_line(resolver_area);
+
+    f.render_widget(
+        Block::new()
+            .title(format!(
+                "Changed By Another Client: {}",
+                conflict.theirs.name
+            ))
+            .borders(Borders::ALL),
+        hint_layout[0],
+    );
+
+    let hint = Paragraph::new(popup_hints(
+        app,
+        &[Action::KeepMine, Action::KeepTheirs, Action::Confirm],
+    ));
+    f.render_widget(hint, hint_layout[1]);
+
+    let vertical_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(1), Constraint::Min(0)])
+        .margin(1)
+        .split(hint_layout[0]);
+
+    let column_width = (vertical_layout[0].width.saturating_sub(13) / 2) as usize;
+    let row = |label: &str, mine: Span<'static>, theirs: Span<'static>| {
+        Line::from(vec![
+            Span::raw(format!("{:<11}", label)),
+            mine,
+            Span::raw(" "),
+            theirs,
+        ])
+    };
+    let cell = |text: String| format!("{:<width$.width$}", text, width = column_width);
+
+    // Header is indented to line up with the rows after the highlight symbol
+    f.render_widget(
+        Paragraph::new(row(
+            "",
+            Span::raw(cell("Mine".to_string())).bold(),
+            Span::raw(cell("Theirs".to_string())).bold(),
+        )),
+        Rect {
+            x: vertical_layout[0].x + 1,
+            width: vertical_layout[0].width.saturating_sub(1),
+            ..vertical_layout[0]
+        },
+    );
+
+    // The side picked for merging is underlined, fields both sides agree on are dimmed
+    let fields: Vec<ListItem> = TaskField::ALL
+        .iter()
+        .zip(conflict.take_theirs)
+        .map(|(field, take_theirs)| {
+            let (mut mine, mut theirs) = (
+                Span::raw(cell(field.display(&conflict.mine))),
+                Span::raw(cell(field.display(&conflict.theirs))),
+            );
+            if !conflict.differs(*field) {
+                mine = mine.dim();
+                theirs = theirs.dim();
+            } else if take_theirs {
+                theirs = theirs.underlined();
+            } else {
+                mine = mine.underlined();
+            }
+            ListItem::new(row(field.label(), mine, theirs))
+        })
+        .collect();
+
+    let field_list = List::new(fields)
+        .highlight_style(app.theme.selected)
+        .highlight_symbol(">");
+    f.render_stateful_widget(
+        field_list,
+        vertical_layout[1],
+        &mut app.conflict_field_state,
+    );
+}
+
+fn draw_history<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let history_area = open_popup(f, 80, 20, &app.theme);
+
+    let title = match &app.task_list.current_taskdate {
+        TaskDate::Task(t) => format!("History: {}", t.name),
+        _ => "History".to_string(),
+    };
+
+    let events: Vec<ListItem> = app
+        .history
+        .iter()
+        .map(|e| {
+            ListItem::new(Line::from(vec![
+                Span::raw(format!(
+                    "{}  ",
+                    e.occurred_at.with_timezone(&Local).format("%b %d %H:%M")
+                )),
+                Span::styled(format!("{:<7} ", e.kind), app.theme.header),
+                Span::raw(e.description()),
+            ]))
+        })
+        .collect();
+
+    let event_list = List::new(events)
+        .block(Block::new().title(title).borders(Borders::ALL))
+        .highlight_style(app.theme.selected)
+        .highlight_symbol(">");
+    f.render_stateful_widget(event_list, history_area, &mut app.history_list_state);
+}
+
+fn draw_messages<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let messages_area = open_popup(f, 80, 20, &app.theme);
+
+    let messages: Vec<ListItem> = app
+        .status
+        .messages()
+        .iter()
+        .map(|m| {
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{}  ", m.at.format("%H:%M:%S"))),
+                Span::styled(
+                    format!("{:<7} ", m.severity.name()),
+                    app.theme.severity_style(m.severity),
+                ),
+                Span::raw(m.text.clone()),
+            ]))
+        })
+        .collect();
+
+    let message_list = List::new(messages)
+        .block(Block::new().title("Messages").borders(Borders::ALL))
+        .highlight_style(app.theme.selected)
+        .highlight_symbol(">");
+    f.render_stateful_widget(message_list, messages_area, &mut app.messages_list_state);
+}
+
+// Recent messages stacked up from the bottom right corner of the view, newest lowest
+fn draw_toasts<B: Backend>(f: &mut Frame<B>, r: Rect, app: &App) {
+    let r = Block::new().borders(Borders::ALL).inner(r); // inside the view's border
+    let duration = Duration::from_secs(app.config.toast_seconds);
+    let toast_width = r.width.min(50);
+    let mut bottom = r.bottom();
+
+    for message in app.status.toasts(duration).iter().rev() {
+        if bottom < r.y + 3 {
+            break;
+        }
+        let area = Rect::new(r.right() - toast_width, bottom - 3, toast_width, 3);
+        bottom -= 3;
+
+        let style = app.theme.severity_style(message.severity);
+        let toast = Paragraph::new(truncate(
+            &message.text,
+            toast_width.saturating_sub(4) as usize,
+        ))
+        .block(
+            Block::new()
+                .title(message.severity.name())
+                .borders(Borders::ALL)
+                .border_style(style)
+                .padding(Padding::horizontal(1)),
+        );
+        clear_popup(f, area, &app.theme);
+        f.render_widget(toast, area);
+    }
+}
+
+// 3x5 block glyphs for the focus countdown, indexed by digit with ':' last
+const BIG_GLYPHS: [[&str; 5]; 11] = [
+    ["███", "█ █", "█ █", "█ █", "███"],
+    ["  █", "  █", "  █", "  █", "  █"],
+    ["███", "  █", "███", "█  ", "███"],
+    ["███", "  █", "███", "  █", "███"],
+    ["█ █", "█ █", "███", "  █", "  █"],
+    ["███", "█  ", "███", "  █", "███"],
+    ["███", "█  ", "███", "█ █", "███"],
+    ["███", "  █", "  █", "  █", "  █"],
+    ["███", "█ █", "███", "█ █", "███"],
+    ["███", "█ █", "███", "  █", "███"],
+    ["   ", " █ ", "   ", " █ ", "   "],
+];
+
+fn big_text(text: &str) -> Vec<Line<'static>> {
+    (0..5)
+        .map(|row| {
+            Line::from(
+                text.chars()
+                    .filter_map(|c| match c {
+                        ':' => Some(BIG_GLYPHS[10][row]),
+                        _ => c.to_digit(10).map(|d| BIG_GLYPHS[d as usize][row]),
+                    })
+                    .collect::<Vec<&str>>()
+                    .join(" "),
+            )
+        })
+        .collect()
+}
+
+fn draw_focus<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let Some(pomodoro) = &app.pomodoro else {
+        return;
+    };
+
+    let focus_area = f.size();
+    clear_popup(f, focus_area, &app.theme);
+
+    let (phase, phase_style) = match pomodoro.phase {
+        PomodoroPhase::Work => ("Focus", app.theme.focus_work),
+        PomodoroPhase::ShortBreak => ("Short Break", app.theme.focus_break),
+        PomodoroPhase::LongBreak => ("Long Break", app.theme.focus_break),
+    };
+
+    let remaining = pomodoro.remaining().num_seconds();
+    let mut content = vec![
+        Line::from(Span::styled(
+            if pomodoro.paused_remaining.is_some() {
+                format!("{} (paused)", phase)
+            } else {
+                phase.to_string()
+            },
+            phase_style,
+        )),
+        Line::from(""),
+    ];
+    content.extend(big_text(&format!(
+        "{:02}:{:02}",
+        remaining / 60,
+        remaining % 60
+    )));
+    content.extend([
+        Line::from(""),
+        Line::from(Span::styled(
+            pomodoro.task_name.clone(),
+            Style::new().italic(),
+        )),
+        Line::from(format!("{} pomodoros completed", pomodoro.completed_work)),
+    ]);
+
+    let vertical_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![
+            Constraint::Min(0),
+            Constraint::Length(content.len() as u16),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(focus_area);
+
+    f.render_widget(
+        Paragraph::new(content).alignment(Alignment::Center),
+        vertical_layout[1],
+    );
+    f.render_widget(
+        Paragraph::new(app.keybind_hints.clone()),
+        vertical_layout[3],
+    );
+}
+
+fn draw_help<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let showing = match app.pop_up {
+        Some(AppPopUp::TaskEditor) => "Task Editor",
+        Some(AppPopUp::CategoryEditor) => "Category Editor",
+        Some(AppPopUp::Rescheduler) => "Reschedule",
+        Some(AppPopUp::DependencyEditor) => "Blocked By",
+        Some(AppPopUp::Stats) => "Time Tracked",
+        Some(AppPopUp::Focus) => "Focus",
+        Some(AppPopUp::ConflictResolver) => "Conflict",
+        Some(AppPopUp::History) => "History",
+        Some(AppPopUp::Messages) => "Messages",
+        Some(AppPopUp::Shifter) => "Shift",
+        Some(AppPopUp::CategoryPicker) => "Move To Category",
+        Some(AppPopUp::QuickAdd) => "Quick Add",
+        Some(AppPopUp::CommandLine) => "Command Line",
+        Some(AppPopUp::CommandPalette) => "Command Palette",
+        None if app.mode == AppMode::Upcoming => "Upcoming",
+        None => "Categories",
+    };
+
+    // Keys are lined up in a column before what they do
+    let groups = app.help_groups();
+    let key_lists: Vec<Vec<String>> = groups
+        .iter()
+        .map(|(_, actions)| {
+            actions
+                .iter()
+                .map(|(_, keys)| {
+                    keys.iter()
+                        .map(|k| k.to_string())
+                        .collect::<Vec<String>>()
+                        .join("/")
+                })
+                .collect()
+        })
+        .collect();
+    let keys_width = key_lists
+        .iter()
+        .flatten()
+        .map(|k| k.width())
+        .max()
+        .unwrap_or(0);
+
+    let mut lines = Vec::new();
+    for ((group, actions), keys) in groups.iter().zip(key_lists) {
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::styled(*group, app.theme.header.underlined()));
+        for ((action, _), keys) in actions.iter().zip(keys) {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {}{}", keys, " ".repeat(keys_width - keys.width() + 2)),
+                    app.theme.header,
+                ),
+                Span::raw(action.name()),
+            ]));
+        }
+    }
+
+    // Tall enough for every binding, up to a couple of lines short of the screen
+    let help_width = 60;
+    let help_height = (lines.len() as u16 + 2).min(f.size().height.saturating_sub(4));
+
+    let help_area = open_popup(f, help_width, help_height, &app.theme);
+
+    let inner_height = help_area.height.saturating_sub(2) as usize;
+    app.help_scroll = app
+        .help_scroll
+        .min(lines.len().saturating_sub(inner_height));
+
+    let help = Paragraph::new(lines)
+        .block(
+            Block::new()
+                .title(truncate(
+                    &format!("Keys: {}", showing),
+                    help_area.width.saturating_sub(2) as usize,
+                ))
+                .borders(Borders::ALL)
+                .padding(Padding::horizontal(1)),
+        )
+        .scroll((app.help_scroll as u16, 0));
+    f.render_widget(help, help_area);
 }
@@ -0,0 +1,34 @@
+use crate::database::IOEvent;
+
+// A user action stored as the events that reverse it and the events that perform it again
+pub struct UndoEntry {
+    pub description: String,
+    pub undo: Vec<IOEvent>,
+    pub redo: Vec<IOEvent>,
+}
+
+#[derive(Default)]
+pub struct UndoHistory {
+    undo_stack: Vec<UndoEntry>,
+    redo_stack: Vec<UndoEntry>,
+}
+
+impl UndoHistory {
+    // A new action replaces whatever was undone before it
+    pub fn record(&mut self, entry: UndoEntry) {
+        self.redo_stack.clear();
+        self.undo_stack.push(entry);
+    }
+
+    pub fn undo(&mut self) -> Option<&UndoEntry> {
+        let entry = self.undo_stack.pop()?;
+        self.redo_stack.push(entry);
+        self.redo_stack.last()
+    }
+
+    pub fn redo(&mut self) -> Option<&UndoEntry> {
+        let entry = self.redo_stack.pop()?;
+        self.undo_stack.push(entry);
+        self.undo_stack.last()
+    }
+}